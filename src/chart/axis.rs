@@ -0,0 +1,357 @@
+use crate::data::bar::Bar;
+use chrono::{DateTime, Datelike, Timelike};
+use chrono_tz::Tz;
+use plotters::coord::{
+    ranged1d::{KeyPointHint, NoDefaultFormatting, Ranged, ReversibleRanged, ValueFormatter},
+    types::{RangedCoordf32, RangedCoordf64},
+};
+use std::ops::Range;
+use strum_macros::Display;
+
+/// Roughly how many pixels a time label needs, including some breathing room.
+const LABEL_WIDTH_PX: u32 = 80;
+
+/// Calendar step between two ticks of the time axis, finest first.
+#[derive(Clone, Copy, PartialEq)]
+enum Spacing {
+    Hours(u32),
+    Days(i32),
+    Weeks,
+    Months,
+}
+const SPACINGS: [Spacing; 9] = [
+    Spacing::Hours(1),
+    Spacing::Hours(2),
+    Spacing::Hours(3),
+    Spacing::Hours(6),
+    Spacing::Hours(12),
+    Spacing::Days(1),
+    Spacing::Days(2),
+    Spacing::Weeks,
+    Spacing::Months,
+];
+
+impl Spacing {
+    /// Ticks go on the bars where the bucket changes.
+    fn bucket(&self, time: &DateTime<Tz>) -> (i32, u32) {
+        match self {
+            Spacing::Hours(n) => (time.num_days_from_ce(), time.hour() / n),
+            Spacing::Days(n) => (time.num_days_from_ce() / n, 0),
+            Spacing::Weeks => (time.iso_week().year(), time.iso_week().week()),
+            Spacing::Months => (time.year(), time.month()),
+        }
+    }
+}
+
+/// x axis of the chart.
+///
+/// Bars are placed at their index, so nights and weekends take no room, while the ticks sit on
+/// calendar boundaries of the bar times in the display timezone: hours within a day, days
+/// across weeks and so on, whatever fits the available width.
+pub(crate) struct TimeAxis {
+    range: Range<f64>,
+    times: Vec<DateTime<Tz>>,
+    spacing: Spacing,
+    /// bar indices carrying a tick
+    ticks: Vec<usize>,
+}
+impl TimeAxis {
+    pub(crate) fn new(bars: &[Bar], tz: Tz, width_px: u32) -> Self {
+        let times = bars
+            .iter()
+            .map(|bar| bar.time.with_timezone(&tz))
+            .collect::<Vec<_>>();
+        let max_ticks = (width_px / LABEL_WIDTH_PX).max(2) as usize;
+        let (spacing, ticks) = SPACINGS
+            .iter()
+            .map(|spacing| (*spacing, boundaries(&times, spacing)))
+            .find(|(_, ticks)| ticks.len() <= max_ticks)
+            .unwrap_or_else(|| {
+                // months still too dense, thin them out evenly
+                let ticks = boundaries(&times, &Spacing::Months);
+                let step = ticks.len().div_ceil(max_ticks);
+                (Spacing::Months, ticks.into_iter().step_by(step).collect())
+            });
+        Self {
+            range: -1.0..bars.len() as f64,
+            times,
+            spacing,
+            ticks,
+        }
+    }
+
+    /// Label of the tick at bar index `x`.
+    fn label(&self, x: &f64) -> String {
+        let ix = x.round() as usize;
+        let Some(time) = self.times.get(ix) else {
+            return String::new();
+        };
+        let new_day = ix == 0 || self.times[ix - 1].date_naive() != time.date_naive();
+        match self.spacing {
+            Spacing::Hours(_) if new_day => time.format("%a %d").to_string(),
+            Spacing::Hours(_) => time.format("%H:%M").to_string(),
+            Spacing::Days(_) | Spacing::Weeks => time.format("%b %d").to_string(),
+            Spacing::Months => time.format("%b %Y").to_string(),
+        }
+    }
+}
+
+impl Ranged for TimeAxis {
+    type FormatOption = NoDefaultFormatting;
+    type ValueType = f64;
+
+    fn map(&self, value: &f64, limit: (i32, i32)) -> i32 {
+        let fraction = (value - self.range.start) / (self.range.end - self.range.start);
+        limit.0 + (fraction * (limit.1 - limit.0) as f64).round() as i32
+    }
+
+    fn key_points<Hint: KeyPointHint>(&self, hint: Hint) -> Vec<f64> {
+        let step = self
+            .ticks
+            .len()
+            .div_ceil(hint.max_num_points().max(1))
+            .max(1);
+        self.ticks
+            .iter()
+            .step_by(step)
+            .map(|ix| *ix as f64)
+            .collect()
+    }
+
+    fn range(&self) -> Range<f64> {
+        self.range.clone()
+    }
+}
+
+impl ValueFormatter<f64> for TimeAxis {
+    fn format_ext(&self, value: &f64) -> String {
+        self.label(value)
+    }
+}
+
+/// Indices of the bars starting a new `spacing` bucket.
+fn boundaries(times: &[DateTime<Tz>], spacing: &Spacing) -> Vec<usize> {
+    (1..times.len())
+        .filter(|ix| spacing.bucket(&times[ix - 1]) != spacing.bucket(&times[*ix]))
+        .collect()
+}
+
+/// Indices of the bars following a gap in trading, e.g. overnight or over a weekend.
+///
+/// A gap is anything longer than one and a half times the typical (median) bar interval.
+pub(crate) fn session_gaps(bars: &[Bar]) -> Vec<usize> {
+    let mut intervals = bars
+        .windows(2)
+        .map(|pair| pair[1].time - pair[0].time)
+        .collect::<Vec<_>>();
+    if intervals.is_empty() {
+        return vec![];
+    }
+    intervals.sort();
+    let typical = intervals[intervals.len() / 2];
+    bars.windows(2)
+        .enumerate()
+        .filter(|(_, pair)| (pair[1].time - pair[0].time) * 2 > typical * 3)
+        .map(|(ix, _)| ix + 1)
+        .collect()
+}
+
+/// How the price axis places prices.
+#[derive(Display, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) enum Scale {
+    #[default]
+    Linear,
+    /// equal ratios take equal room, so a move from 100 to 200 looks like one from 1000 to 2000
+    Log,
+    /// linear, labelled in percent change from the close of the first bar
+    Percent,
+}
+impl Scale {
+    /// The next scale, wrapping around to the first.
+    pub(crate) fn next(&self) -> Self {
+        match self {
+            Scale::Linear => Scale::Log,
+            Scale::Log => Scale::Percent,
+            Scale::Percent => Scale::Linear,
+        }
+    }
+}
+
+/// y axis of a pane, linear unless [`ValueAxis::scaled`].
+#[derive(Clone)]
+pub(crate) struct ValueAxis {
+    range: Range<f32>,
+    scale: Scale,
+    /// the price [`Scale::Percent`] labels are relative to
+    base: f32,
+}
+impl ValueAxis {
+    pub(crate) fn new(range: Range<f32>) -> Self {
+        Self {
+            range,
+            scale: Scale::Linear,
+            base: 0.0,
+        }
+    }
+
+    /// Places the values by `scale`, percentages being relative to `base`. Stays linear where
+    /// the scale is undefined: a log scale reaching zero or below, a percentage of zero.
+    pub(crate) fn scaled(self, scale: Scale, base: f32) -> Self {
+        let scale = match scale {
+            Scale::Log if self.range.start <= 0.0 => Scale::Linear,
+            Scale::Percent if base == 0.0 => Scale::Linear,
+            scale => scale,
+        };
+        Self {
+            scale,
+            base,
+            ..self
+        }
+    }
+
+    /// The scale the values are placed by, linear where the one asked for is undefined.
+    pub(crate) fn scale(&self) -> Scale {
+        self.scale
+    }
+
+    fn percent(&self, value: f32) -> f64 {
+        (value as f64 / self.base as f64 - 1.0) * 100.0
+    }
+
+    /// Decimals of the percent labels, enough to tell apart the ticks of the range.
+    fn percent_decimals(&self) -> usize {
+        let span = self.percent(self.range.end) - self.percent(self.range.start);
+        (-(span / 5.0).log10().floor()).clamp(0.0, 4.0) as usize
+    }
+}
+
+impl Ranged for ValueAxis {
+    type FormatOption = NoDefaultFormatting;
+    type ValueType = f32;
+
+    fn map(&self, value: &f32, limit: (i32, i32)) -> i32 {
+        let (value, start, end) = match self.scale {
+            // values below the range are off the chart anyway, keep them finite
+            Scale::Log => (
+                (value.max(f32::MIN_POSITIVE) as f64).ln(),
+                (self.range.start as f64).ln(),
+                (self.range.end as f64).ln(),
+            ),
+            Scale::Linear | Scale::Percent => (
+                *value as f64,
+                self.range.start as f64,
+                self.range.end as f64,
+            ),
+        };
+        let fraction = (value - start) / (end - start);
+        limit.0 + (fraction * (limit.1 - limit.0) as f64).round() as i32
+    }
+
+    fn key_points<Hint: KeyPointHint>(&self, hint: Hint) -> Vec<f32> {
+        let max_points = hint.max_num_points().max(1);
+        let linear = || RangedCoordf32::from(self.range.clone()).key_points(max_points);
+        match self.scale {
+            Scale::Linear => linear(),
+            // 1, 2 and 5 of every decade, or only the 1s if those are too many; within less
+            // than a decade round values are as good as any
+            Scale::Log if self.range.end / self.range.start >= 10.0 => {
+                let decades =
+                    self.range.start.log10().floor() as i32..=self.range.end.log10().ceil() as i32;
+                let ticks = |mantissas: &[f32]| {
+                    decades
+                        .clone()
+                        .flat_map(|exponent| {
+                            mantissas.iter().map(move |m| m * 10f32.powi(exponent))
+                        })
+                        .filter(|value| self.range.contains(value))
+                        .collect::<Vec<_>>()
+                };
+                let ticks = Some(ticks(&[1.0, 2.0, 5.0]))
+                    .filter(|ticks| ticks.len() <= max_points)
+                    .unwrap_or_else(|| ticks(&[1.0]));
+                let step = ticks.len().div_ceil(max_points).max(1);
+                ticks.into_iter().step_by(step).collect()
+            }
+            Scale::Log => linear(),
+            // round percentages, at the prices they stand for
+            Scale::Percent => {
+                let percents = self.percent(self.range.start)..self.percent(self.range.end);
+                RangedCoordf64::from(percents)
+                    .key_points(max_points)
+                    .into_iter()
+                    .map(|percent| (self.base as f64 * (1.0 + percent / 100.0)) as f32)
+                    .collect()
+            }
+        }
+    }
+
+    fn range(&self) -> Range<f32> {
+        self.range.clone()
+    }
+}
+
+impl ReversibleRanged for ValueAxis {
+    fn unmap(&self, input: i32, limit: (i32, i32)) -> Option<f32> {
+        if limit.0 == limit.1 {
+            return None;
+        }
+        let fraction = (input - limit.0) as f64 / (limit.1 - limit.0) as f64;
+        let (start, end) = (self.range.start as f64, self.range.end as f64);
+        Some(match self.scale {
+            Scale::Log => (start.ln() + fraction * (end.ln() - start.ln())).exp() as f32,
+            Scale::Linear | Scale::Percent => (start + fraction * (end - start)) as f32,
+        })
+    }
+}
+
+impl ValueFormatter<f32> for ValueAxis {
+    fn format_ext(&self, value: &f32) -> String {
+        match self.scale {
+            Scale::Linear | Scale::Log => RangedCoordf32::format(value),
+            Scale::Percent => {
+                let label = format!("{:+.*}%", self.percent_decimals(), self.percent(*value));
+                // the base itself is no change, not a rounding error either way
+                match label
+                    .trim_start_matches(['+', '-'])
+                    .trim_start_matches(['0', '.'])
+                {
+                    "%" => "0%".to_string(),
+                    _ => label,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn linear_where_the_scale_is_undefined() {
+        assert_eq!(
+            ValueAxis::new(1.0..10.0).scaled(Scale::Log, 1.0).scale(),
+            Scale::Log
+        );
+        assert_eq!(
+            ValueAxis::new(-1.0..10.0).scaled(Scale::Log, 1.0).scale(),
+            Scale::Linear
+        );
+        assert_eq!(
+            ValueAxis::new(0.0..10.0).scaled(Scale::Log, 1.0).scale(),
+            Scale::Linear
+        );
+        assert_eq!(
+            ValueAxis::new(1.0..10.0)
+                .scaled(Scale::Percent, 2.0)
+                .scale(),
+            Scale::Percent
+        );
+        assert_eq!(
+            ValueAxis::new(1.0..10.0)
+                .scaled(Scale::Percent, 0.0)
+                .scale(),
+            Scale::Linear
+        );
+    }
+}
@@ -0,0 +1,214 @@
+use crate::chart::render::{ChartSpec, chart_image, draw_chart};
+use crate::data::{
+    bar::Series,
+    data::AssetClass,
+    provider::{ALL_TIME, DataProvider, Instrument},
+    resample::resample,
+};
+use crate::theme::Theme;
+use color_eyre::{
+    Result,
+    eyre::{Ok, WrapErr, eyre},
+};
+use image::{RgbImage, imageops};
+use plotters::{
+    prelude::*,
+    style::text_anchor::{HPos, Pos, VPos},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
+
+/// Height of the strip with the symbol above every chart of a contact sheet.
+const SHEET_LABEL_HEIGHT: u32 = 32;
+/// Space left of the symbol in that strip.
+const SHEET_LABEL_INSET: i32 = 10;
+
+/// Width and height in pixels, e.g. `1600x900`: of an exported chart, or of a terminal cell.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(try_from = "String", into = "String")]
+pub(crate) struct PixelSize {
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+}
+impl Default for PixelSize {
+    fn default() -> Self {
+        Self {
+            width: 1600,
+            height: 900,
+        }
+    }
+}
+impl FromStr for PixelSize {
+    type Err = color_eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (width, height) = s
+            .split_once('x')
+            .ok_or_else(|| eyre!("Expected <width>x<height>, e.g. 1600x900"))?;
+        let size = Self {
+            width: width.trim().parse().wrap_err("Invalid width")?,
+            height: height.trim().parse().wrap_err("Invalid height")?,
+        };
+        if size.width == 0 || size.height == 0 {
+            return Err(eyre!("Expected a width and height above 0"));
+        }
+        Ok(size)
+    }
+}
+impl fmt::Display for PixelSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)
+    }
+}
+// as `1600x900` in the config file too
+impl TryFrom<String> for PixelSize {
+    type Error = color_eyre::Report;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+impl From<PixelSize> for String {
+    fn from(size: PixelSize) -> Self {
+        size.to_string()
+    }
+}
+
+/// Draws the chart of `symbol` described by `spec` into `<symbol>_<timeframe>.png` and `.svg`
+/// in `dir`, both of `size` regardless of the terminal. The paths written.
+///
+/// Blocking; `series` is expected to be resampled to the timeframe of `spec` already.
+pub(crate) fn export(
+    series: &Series,
+    asset_class: AssetClass,
+    symbol: &str,
+    spec: &ChartSpec,
+    size: PixelSize,
+    dir: &Path,
+) -> Result<[PathBuf; 2]> {
+    let stem = format!("{symbol}_{}", spec.timeframe);
+    let png = dir.join(format!("{stem}.png"));
+    let svg = dir.join(format!("{stem}.svg"));
+    let pixels = (size.width, size.height);
+
+    let root = BitMapBackend::new(&png, pixels).into_drawing_area();
+    draw_chart(&root, series, asset_class, spec).wrap_err_with(|| format!("Charting {symbol}"))?;
+    // manually call the present function to avoid the IO failure being ignored silently
+    root.present()
+        .wrap_err_with(|| format!("Writing {}", png.display()))?;
+    drop(root); // to release the borrow of the path
+
+    let root = SVGBackend::new(&svg, pixels).into_drawing_area();
+    draw_chart(&root, series, asset_class, spec).wrap_err_with(|| format!("Charting {symbol}"))?;
+    root.present()
+        .wrap_err_with(|| format!("Writing {}", svg.display()))?;
+    drop(root);
+
+    Ok([png, svg])
+}
+
+/// [`export`] of the bars `provider` has for `symbol`, drawn on a blocking task.
+pub(crate) async fn export_symbol(
+    provider: Arc<dyn DataProvider>,
+    symbol: String,
+    asset_class: AssetClass,
+    spec: ChartSpec,
+    size: PixelSize,
+    dir: PathBuf,
+) -> Result<[PathBuf; 2]> {
+    let series = provider.bars(&symbol, ALL_TIME).await?;
+    tokio::task::spawn_blocking(move || {
+        let series = resample(series, spec.timeframe, asset_class);
+        export(&series, asset_class, &symbol, &spec, size, &dir)
+    })
+    .await?
+}
+
+/// Draws the chart of every one of `instruments` into `<symbol>_<timeframe>.png` in `dir`, and
+/// all of them tiled into `contact_sheet_<timeframe>.png`, every chart `size`. The paths written,
+/// the contact sheet last.
+pub(crate) async fn render_batch(
+    provider: Arc<dyn DataProvider>,
+    instruments: Vec<Instrument>,
+    spec: ChartSpec,
+    size: PixelSize,
+    dir: PathBuf,
+) -> Result<Vec<PathBuf>> {
+    let spec = Arc::new(spec);
+    let mut charts = vec![];
+    let mut paths = vec![];
+    for instrument in instruments {
+        let series = provider.bars(instrument.symbol(), ALL_TIME).await?;
+        let path = dir.join(format!("{}_{}.png", instrument.symbol(), spec.timeframe));
+        let spec = spec.clone();
+        let (symbol, image, path) = tokio::task::spawn_blocking(move || {
+            let series = resample(series, spec.timeframe, instrument.asset_class);
+            let (image, _) = chart_image(
+                &series,
+                instrument.asset_class,
+                &spec,
+                (size.width, size.height),
+            )
+            .wrap_err_with(|| format!("Charting {}", instrument.symbol()))?;
+            image
+                .save(&path)
+                .wrap_err_with(|| format!("Writing {}", path.display()))?;
+            Ok((instrument.symbol, image, path))
+        })
+        .await??;
+        charts.push((symbol, image));
+        paths.push(path);
+    }
+
+    let path = dir.join(format!("contact_sheet_{}.png", spec.timeframe));
+    tokio::task::spawn_blocking(move || {
+        contact_sheet(&charts, size, spec.theme.theme())?
+            .save(&path)
+            .wrap_err_with(|| format!("Writing {}", path.display()))?;
+        paths.push(path);
+        Ok(paths)
+    })
+    .await?
+}
+
+/// `charts` tiled in a grid as square as it gets, each below a strip with its symbol.
+fn contact_sheet(
+    charts: &[(String, RgbImage)],
+    size: PixelSize,
+    theme: &Theme,
+) -> Result<RgbImage> {
+    let columns = (charts.len() as f64).sqrt().ceil().max(1.0) as u32;
+    let rows = (charts.len() as u32).div_ceil(columns).max(1);
+    let tile = (size.width, size.height + SHEET_LABEL_HEIGHT);
+    let (width, height) = (columns * tile.0, rows * tile.1);
+
+    let mut buffer = vec![0u8; width as usize * height as usize * 3]; // RGB pixel format
+    let root = BitMapBackend::with_buffer(&mut buffer, (width, height)).into_drawing_area();
+    root.fill(&theme.background)?;
+    let label_style = TextStyle::from(theme.font.into_font())
+        .color(&theme.label)
+        .pos(Pos::new(HPos::Left, VPos::Center));
+    let origins =
+        (0..charts.len() as u32).map(|ix| ((ix % columns) * tile.0, (ix / columns) * tile.1));
+    for ((symbol, _), (x, y)) in charts.iter().zip(origins.clone()) {
+        let position = (
+            x as i32 + SHEET_LABEL_INSET,
+            (y + SHEET_LABEL_HEIGHT / 2) as i32,
+        );
+        root.draw_text(symbol, &label_style, position)?;
+    }
+    root.present()?;
+    drop(root); // to release the mutable borrow of the buffer
+
+    let mut sheet = RgbImage::from_raw(width, height, buffer)
+        .ok_or_else(|| eyre!("Failed to construct ImageBuffer"))?;
+    for ((_, chart), (x, y)) in charts.iter().zip(origins) {
+        imageops::replace(&mut sheet, chart, x.into(), (y + SHEET_LABEL_HEIGHT).into());
+    }
+    Ok(sheet)
+}
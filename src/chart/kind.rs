@@ -0,0 +1,253 @@
+use crate::data::bar::{Bar, Series};
+use crate::indicators::bands::atr;
+use color_eyre::{Result, eyre::eyre};
+use serde::{Deserialize, Serialize};
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use strum_macros::Display;
+
+/// Period of the ATR an automatic Renko box derives from.
+const RENKO_ATR_PERIOD: usize = 14;
+/// Share of the price range a Renko box falls back to when there are too few bars for an ATR.
+const RENKO_FALLBACK_SHARE: f32 = 0.05;
+/// Share of the price a Renko box is at least, well above the rounding of an `f32` price, so
+/// that every brick moves.
+const RENKO_MIN_SHARE: f32 = 1e-4;
+/// Bricks a single bar completes at most, however far it moved.
+const RENKO_MAX_BRICKS_PER_BAR: usize = 1_000;
+/// Bricks a chart keeps at most, the latest ones.
+const RENKO_MAX_BRICKS: usize = 10_000;
+
+/// How the price pane shows the bars.
+#[derive(Display, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) enum ChartKind {
+    #[default]
+    Candles,
+    /// open and close ticks left and right of a high-low line
+    #[strum(to_string = "OHLC")]
+    Ohlc,
+    /// the closes
+    Line,
+    /// the closes, filled below
+    Area,
+    /// candles of averaged prices, which smooth out the noise of trends
+    #[strum(to_string = "Heikin-Ashi")]
+    HeikinAshi,
+    /// bricks of a fixed price size, one per move of that size, however long it took
+    Renko,
+}
+impl ChartKind {
+    /// The next kind, wrapping around to the first.
+    pub(crate) fn next(&self) -> Self {
+        match self {
+            ChartKind::Candles => ChartKind::Ohlc,
+            ChartKind::Ohlc => ChartKind::Line,
+            ChartKind::Line => ChartKind::Area,
+            ChartKind::Area => ChartKind::HeikinAshi,
+            ChartKind::HeikinAshi => ChartKind::Renko,
+            ChartKind::Renko => ChartKind::Candles,
+        }
+    }
+
+    /// The bars this kind draws, and the overlays are computed from: averaged ones for
+    /// Heikin-Ashi, bricks for Renko, `series` as is otherwise.
+    pub(crate) fn transform(&self, series: &Series, box_size: BoxSize) -> Series {
+        match self {
+            ChartKind::HeikinAshi => heikin_ashi(series),
+            ChartKind::Renko => renko(series, box_size),
+            _ => series.clone(),
+        }
+    }
+}
+
+/// Price size of a Renko brick.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
+#[serde(try_from = "String", into = "String")]
+pub(crate) enum BoxSize {
+    /// the latest average true range, which adapts to any instrument
+    #[default]
+    Atr,
+    Fixed(f32),
+}
+
+// by bits, so specs holding a box size can be cache keys; parsing never yields NaN
+impl PartialEq for BoxSize {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (BoxSize::Atr, BoxSize::Atr) => true,
+            (BoxSize::Fixed(a), BoxSize::Fixed(b)) => a.to_bits() == b.to_bits(),
+            _ => false,
+        }
+    }
+}
+impl Eq for BoxSize {}
+impl Hash for BoxSize {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        if let BoxSize::Fixed(size) = self {
+            size.to_bits().hash(state);
+        }
+    }
+}
+
+/// Parses `atr` or a positive price, e.g. `250`.
+impl FromStr for BoxSize {
+    type Err = color_eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s.trim().eq_ignore_ascii_case("atr") {
+            return Ok(BoxSize::Atr);
+        }
+        s.trim()
+            .parse::<f32>()
+            .ok()
+            .filter(|size| size.is_normal() && *size > 0.0)
+            .map(BoxSize::Fixed)
+            .ok_or_else(|| eyre!("expected atr or a positive price, e.g. 250"))
+    }
+}
+// as `atr` or `250` in the config file
+impl TryFrom<String> for BoxSize {
+    type Error = color_eyre::Report;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+impl From<BoxSize> for String {
+    fn from(size: BoxSize) -> Self {
+        match size {
+            BoxSize::Atr => "atr".into(),
+            BoxSize::Fixed(size) => size.to_string(),
+        }
+    }
+}
+
+fn heikin_ashi(series: &Series) -> Series {
+    let mut bars: Vec<Bar> = Vec::with_capacity(series.bars().len());
+    for bar in series.bars() {
+        let close = (bar.open + bar.high + bar.low + bar.close) / 4.0;
+        let open = match bars.last() {
+            Some(previous) => (previous.open + previous.close) / 2.0,
+            None => (bar.open + bar.close) / 2.0,
+        };
+        bars.push(Bar {
+            open,
+            high: bar.high.max(open).max(close),
+            low: bar.low.min(open).min(close),
+            close,
+            ..bar.clone()
+        });
+    }
+    Series::new(bars)
+}
+
+/// Bricks of the closes, each opening where the previous one closed, or where it opened when
+/// reversing. A brick carries the time of the bar that completed it, and no volume.
+///
+/// The box is at least [`RENKO_MIN_SHARE`] of the price, and the bricks are capped per bar and in
+/// total, so that a tiny box can't stall the chart.
+fn renko(series: &Series, box_size: BoxSize) -> Series {
+    let source = series.bars();
+    let Some(first) = source.first() else {
+        return Series::default();
+    };
+    let size = match box_size {
+        BoxSize::Fixed(size) => Some(size),
+        BoxSize::Atr => atr(source, RENKO_ATR_PERIOD).last().copied().flatten(),
+    }
+    .filter(|size| *size > 0.0)
+    .unwrap_or_else(|| {
+        let range = series.price_range().unwrap_or_default();
+        (range.end - range.start) * RENKO_FALLBACK_SHARE
+    });
+    let range = series.price_range().unwrap_or_default();
+    let size = size
+        .max(range.start.abs().max(range.end.abs()) * RENKO_MIN_SHARE)
+        .max(f32::EPSILON);
+
+    let mut bricks: Vec<Bar> = vec![];
+    // body of the last brick, the first close before any
+    let (mut bottom, mut top) = (first.close, first.close);
+    for bar in source {
+        let mut count = 0;
+        while bar.close >= top + size && count < RENKO_MAX_BRICKS_PER_BAR {
+            bricks.push(Bar::new(bar.time, top, top + size, top, top + size));
+            (bottom, top) = (top, top + size);
+            count += 1;
+        }
+        while bar.close <= bottom - size && count < RENKO_MAX_BRICKS_PER_BAR {
+            bricks.push(Bar::new(
+                bar.time,
+                bottom,
+                bottom,
+                bottom - size,
+                bottom - size,
+            ));
+            (bottom, top) = (bottom - size, bottom);
+            count += 1;
+        }
+        // dropping the oldest in batches rather than brick by brick
+        if bricks.len() > 2 * RENKO_MAX_BRICKS {
+            bricks.drain(..bricks.len() - RENKO_MAX_BRICKS);
+        }
+    }
+    bricks.drain(..bricks.len().saturating_sub(RENKO_MAX_BRICKS));
+    Series::new(bricks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::bar::test_support::bars;
+
+    #[test]
+    fn renko_boxes_no_finer_than_the_price() {
+        let series = Series::new(bars(&[
+            (60000.0, 60000.0, 60000.0, 60000.0),
+            (60000.0, 60060.0, 60000.0, 60060.0),
+        ]));
+        // a box of 6.006, a ten-thousandth of the highest price
+        let bricks = ChartKind::Renko.transform(&series, BoxSize::Fixed(0.001));
+        assert_eq!(bricks.bars().len(), 9);
+
+        // too few bars for an ATR, and no range to fall back to
+        let flat = Series::new(bars(&[(60000.0, 60000.0, 60000.0, 60000.0); 5]));
+        assert!(
+            ChartKind::Renko
+                .transform(&flat, BoxSize::Atr)
+                .bars()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn renko_bricks_capped() {
+        let series = Series::new(bars(&[(1.0, 1.0, 1.0, 1.0), (1.0, 1e4, 1.0, 1e4)]));
+        let bricks = ChartKind::Renko.transform(&series, BoxSize::Fixed(1.0));
+        assert_eq!(bricks.bars().len(), RENKO_MAX_BRICKS_PER_BAR);
+
+        // swinging between 1 and 101, some hundred bricks a bar
+        let series = Series::new(bars(
+            &(0..2 * RENKO_MAX_BRICKS / 100)
+                .map(|ix| if ix % 2 == 0 { 1.0 } else { 101.0 })
+                .map(|close| (close, close, close, close))
+                .collect::<Vec<_>>(),
+        ));
+        let bricks = ChartKind::Renko.transform(&series, BoxSize::Fixed(1.0));
+        assert_eq!(bricks.bars().len(), RENKO_MAX_BRICKS);
+        assert_eq!(
+            bricks.bars().last().unwrap().time,
+            series.bars().last().unwrap().time
+        );
+    }
+
+    #[test]
+    fn box_sizes() {
+        assert_eq!("ATR".parse::<BoxSize>().unwrap(), BoxSize::Atr);
+        assert_eq!("250".parse::<BoxSize>().unwrap(), BoxSize::Fixed(250.0));
+        for invalid in ["0", "-1", "inf", "NaN", "1e-40", "box"] {
+            assert!(invalid.parse::<BoxSize>().is_err(), "{invalid}");
+        }
+    }
+}
@@ -0,0 +1,990 @@
+use crate::chart::axis::{Scale, TimeAxis, ValueAxis, session_gaps};
+use crate::chart::kind::{BoxSize, ChartKind};
+use crate::chart::viewport::Viewport;
+use crate::data::{
+    bar::{Bar, Series},
+    data::AssetClass,
+    resample::{Timeframe, sessions},
+};
+use crate::indicators::{
+    bands::{Band, BandKind},
+    moving_average::MovingAverage,
+    oscillators::{Macd, Oscillator, macd, rsi, stochastic},
+    patterns::{Bias, scan},
+    pivots::{FloorPivots, SwingKind, SwingStrength, floor_pivots, swings},
+    zones::zones,
+};
+use crate::theme::{Theme, ThemeName};
+use chrono_tz::Tz;
+use color_eyre::{Result, eyre::eyre};
+use image::RgbImage;
+use plotters::{
+    coord::{Shift, ranged1d::ReversibleRanged},
+    prelude::*,
+    style::text_anchor::{HPos, Pos, VPos},
+};
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+/// Half the width of a candle body or volume bar, in bars...
+const BAR_HALF_WIDTH: f64 = 0.35;
+/// ...unless that would make bodies wider than this many pixels...
+const MAX_BODY_WIDTH: f64 = 24.0;
+/// ...or the bars are fewer pixels apart than this, too few for a visible gap between bodies.
+const MIN_GAP_SPACING: f64 = 3.0;
+/// Height of the time labels below the bottom pane.
+const X_LABEL_AREA: u32 = 25;
+/// Width of the value labels right of every pane, the same for all so they share the x mapping.
+const Y_LABEL_AREA: u32 = 60;
+/// Room above the plot of every pane, so the labels of stacked panes do not run into each other.
+const PANE_MARGIN_TOP: u32 = 10;
+/// Share of the chart height each lower pane (volume, oscillators) takes...
+const LOWER_PANE_RATIO: f64 = 0.2;
+/// ...unless there are so many that they would squeeze the price pane below this share.
+const MIN_PRICE_PANE_RATIO: f64 = 0.4;
+/// Pixels of the price pane, at least; charts too short for that and the lower panes go without
+/// the lower panes.
+const MIN_PRICE_PANE_HEIGHT: u32 = 60;
+/// Share of the price range added above and below it when the candles are annotated.
+const ANNOTATION_MARGIN: f32 = 0.08;
+/// Font size of the candlestick pattern labels, smaller than the axis labels to fit above candles.
+const PATTERN_FONT_SIZE: u32 = 12;
+/// Where the pane titles go, relative to the top left corner of the pane.
+const PANE_TITLE_OFFSET: (i32, i32) = (6, PANE_MARGIN_TOP as i32);
+
+/// Visual settings of a chart; everything besides the data and the size that changes its pixels.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub(crate) struct ChartSpec {
+    /// timezone the time axis is labelled in
+    pub(crate) tz: Tz,
+    /// bars are resampled to this before drawing
+    pub(crate) timeframe: Timeframe,
+    /// volume histogram below the candles, if the bars have volume
+    pub(crate) show_volume: bool,
+    pub(crate) theme: ThemeName,
+    /// how the price pane shows the bars
+    pub(crate) kind: ChartKind,
+    /// how the price axis places and labels prices
+    pub(crate) scale: Scale,
+    /// the bars shown, the price axis fits them
+    pub(crate) viewport: Viewport,
+    /// brick size of [`ChartKind::Renko`]
+    pub(crate) renko_box: BoxSize,
+    /// moving averages of the closes drawn over the candles, listed in a legend
+    pub(crate) moving_averages: Vec<MovingAverage>,
+    pub(crate) show_moving_averages: bool,
+    /// envelopes drawn behind the candles, shaded between their upper and lower lines
+    pub(crate) bands: Vec<BandKind>,
+    pub(crate) show_bands: bool,
+    /// each in its own pane below the price chart
+    pub(crate) oscillators: BTreeSet<Oscillator>,
+    /// swing high/low markers and the floor pivot levels of every session
+    pub(crate) show_pivots: bool,
+    pub(crate) swing_strength: SwingStrength,
+    /// candlestick pattern labels above (bearish) or below (bullish) the completing candle
+    pub(crate) show_patterns: bool,
+    /// support (below the last close) and resistance (above) zones, clustered from the swings
+    pub(crate) show_zones: bool,
+}
+impl Default for ChartSpec {
+    fn default() -> Self {
+        Self {
+            tz: Tz::UTC,
+            timeframe: Timeframe::default(),
+            show_volume: true,
+            theme: ThemeName::default(),
+            kind: ChartKind::default(),
+            scale: Scale::default(),
+            viewport: Viewport::default(),
+            renko_box: BoxSize::default(),
+            moving_averages: MovingAverage::defaults(),
+            show_moving_averages: true,
+            bands: BandKind::defaults(),
+            show_bands: true,
+            oscillators: BTreeSet::new(),
+            show_pivots: false,
+            swing_strength: SwingStrength::default(),
+            show_patterns: false,
+            show_zones: false,
+        }
+    }
+}
+
+/// Draws the chart of `series` onto `root`: the price pane in the [`ChartKind`] of `spec`, with
+/// the enabled lower panes (volume, ...) stacked below it, all showing the [`Viewport`] of `spec`.
+/// The trading sessions of `asset_class` delimit the floor pivots.
+///
+/// Backend agnostic, so the same chart can end up in a terminal image or in a file. Returns where
+/// the bars ended up on `root`.
+pub(crate) fn draw_chart<DB>(
+    root: &DrawingArea<DB, Shift>,
+    series: &Series,
+    asset_class: AssetClass,
+    spec: &ChartSpec,
+) -> Result<ChartGeometry>
+where
+    DB: DrawingBackend,
+    DB::ErrorType: 'static,
+{
+    root.fill(&spec.theme.theme().background)?;
+    // overlays and lower panes follow the transformed bars, e.g. averages of the Renko bricks
+    let all = spec.kind.transform(series, spec.renko_box);
+    let window = Window::new(&all, spec.viewport, root.dim_in_pixel().0);
+
+    let mut lower_panes = vec![];
+    if spec.show_volume && window.series.has_volume() {
+        lower_panes.push(LowerPane::Volume);
+    }
+    lower_panes.extend(spec.oscillators.iter().copied().map(LowerPane::Oscillator));
+
+    let (price_area, lower_areas) = split_panes(root, lower_panes.len());
+    lower_panes.truncate(lower_areas.len());
+    let mut geometry = draw_price_pane(
+        &price_area,
+        &window,
+        asset_class,
+        spec,
+        lower_panes.is_empty(),
+    )?;
+    if !lower_panes.is_empty() {
+        geometry.plot_bottom = root.dim_in_pixel().1 as i32 - X_LABEL_AREA as i32;
+    }
+    for (ix, (pane, area)) in lower_panes.iter().zip(lower_areas).enumerate() {
+        let x_labels = ix == lower_panes.len() - 1;
+        match pane {
+            LowerPane::Volume => draw_volume_pane(&area, &window, spec, x_labels)?,
+            LowerPane::Oscillator(oscillator) => {
+                draw_oscillator_pane(&area, &window, spec, *oscillator, x_labels)?
+            }
+        }
+    }
+    Ok(geometry)
+}
+
+/// [`draw_chart`] into an RGB image of `size` (width, height) pixels, the same whether it ends
+/// up in a terminal or in a file.
+///
+/// Blocking, the drawing takes a while for long series.
+pub(crate) fn chart_image(
+    series: &Series,
+    asset_class: AssetClass,
+    spec: &ChartSpec,
+    size: (u32, u32),
+) -> Result<(RgbImage, ChartGeometry)> {
+    let (width, height) = size;
+    let mut buffer = vec![0u8; width as usize * height as usize * 3]; // RGB pixel format
+    let root = BitMapBackend::with_buffer(&mut buffer, size).into_drawing_area();
+    let geometry = draw_chart(&root, series, asset_class, spec)?;
+    // manually call the present function to avoid the IO failure being ignored silently
+    root.present()?;
+    drop(root); // to release the mutable borrow of the buffer
+    let image = RgbImage::from_raw(width, height, buffer)
+        .ok_or_else(|| eyre!("Failed to construct ImageBuffer"))?;
+    Ok((image, geometry))
+}
+
+/// Where [`draw_chart`] put the bars, in pixels of its drawing area, to find them again in the
+/// image, e.g. for a crosshair.
+#[derive(Clone)]
+pub(crate) struct ChartGeometry {
+    /// center of every bar in view
+    pub(crate) bar_x: Vec<i32>,
+    /// the plotting area shared by all panes, without the axis labels
+    pub(crate) plot_x: Range<i32>,
+    /// bottom of the plotting area of the lowest pane
+    pub(crate) plot_bottom: i32,
+    /// the plotting area of the price pane, top to bottom
+    pub(crate) price_y: Range<i32>,
+    pub(crate) price_axis: ValueAxis,
+    /// rows of the lowest and highest price of `price_axis`
+    pub(crate) price_limit: (i32, i32),
+}
+impl ChartGeometry {
+    /// The price at pixel row `y` of the price pane, `None` outside of it.
+    pub(crate) fn price_at(&self, y: i32) -> Option<f32> {
+        if !self.price_y.contains(&y) {
+            return None;
+        }
+        self.price_axis.unmap(y, self.price_limit)
+    }
+
+    /// The pixel row of `price` in the price pane.
+    pub(crate) fn y_of(&self, price: f32) -> i32 {
+        self.price_axis.map(&price, self.price_limit)
+    }
+}
+
+/// The values of the indicator lines of `spec` (overlays and oscillators, shown or not), each
+/// under the name of its line, at the bars of its [`Viewport`] of `series`.
+///
+/// `series` is what the chart shows, i.e. [transformed](ChartKind::transform) by its kind.
+pub(crate) fn readings(series: &Series, spec: &ChartSpec) -> Vec<(String, Vec<Option<f32>>)> {
+    let window = spec.viewport.window(series.bars().len());
+    let clip = |values: &[Option<f32>]| values[window.clone()].to_vec();
+    let closes = series.closes();
+    let mut readings = vec![];
+    if spec.show_moving_averages {
+        for moving_average in &spec.moving_averages {
+            readings.push((
+                moving_average.to_string(),
+                clip(&moving_average.compute(&closes)),
+            ));
+        }
+    }
+    if spec.show_bands {
+        for kind in &spec.bands {
+            let band = kind.compute(series.bars());
+            readings.push((format!("{kind} upper"), clip(&band.upper)));
+            readings.push((format!("{kind} lower"), clip(&band.lower)));
+        }
+    }
+    for oscillator in &spec.oscillators {
+        match oscillator {
+            Oscillator::Rsi => readings.push((oscillator.to_string(), clip(&rsi(&closes)))),
+            Oscillator::Macd => {
+                let macd = macd(&closes);
+                readings.push(("MACD".to_string(), clip(&macd.line)));
+                readings.push(("MACD signal".to_string(), clip(&macd.signal)));
+            }
+            Oscillator::Stochastic => {
+                let stochastic = stochastic(series.bars());
+                readings.push(("Stoch %K".to_string(), clip(&stochastic.k)));
+                readings.push(("Stoch %D".to_string(), clip(&stochastic.d)));
+            }
+        }
+    }
+    readings
+}
+
+/// The bars in view, and all of them for the indicators.
+///
+/// Indicators are computed over all bars, so they have warmed up by the first bar in view, and
+/// then [`clip`](Window::clip)ped like the bars. The panes index the bars in view from 0.
+struct Window<'a> {
+    all: &'a Series,
+    /// of `all`
+    range: Range<usize>,
+    /// the bars in `range`
+    series: Series,
+    /// of candle bodies and volume bars, in bars
+    bar_half_width: f64,
+}
+impl<'a> Window<'a> {
+    /// The bars of `viewport` on a chart `width` pixels wide.
+    fn new(all: &'a Series, viewport: Viewport, width: u32) -> Self {
+        let range = viewport.window(all.bars().len());
+        let series = Series::new(all.bars()[range.clone()].to_vec());
+        // the x axis reaches half a bar beyond either end
+        let spacing = width.saturating_sub(Y_LABEL_AREA) as f64 / (range.len() + 1) as f64;
+        let bar_half_width = if spacing < MIN_GAP_SPACING {
+            0.5
+        } else {
+            BAR_HALF_WIDTH.min(MAX_BODY_WIDTH / 2.0 / spacing)
+        };
+        Self {
+            all,
+            range,
+            series,
+            bar_half_width,
+        }
+    }
+
+    /// The values of the bars in view, of values computed for all bars.
+    fn clip<T: Clone>(&self, values: &[T]) -> Vec<T> {
+        values[self.range.clone()].to_vec()
+    }
+
+    /// The index in view of bar `ix` of all bars, if in view.
+    fn visible_ix(&self, ix: usize) -> Option<usize> {
+        self.range.contains(&ix).then(|| ix - self.range.start)
+    }
+}
+
+/// Panes below the price chart, sharing its x axis.
+enum LowerPane {
+    Volume,
+    Oscillator(Oscillator),
+}
+
+/// Splits `root` into the price pane and `count` lower panes below it, or none if `root` is too
+/// short for them.
+fn split_panes<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    count: usize,
+) -> (DrawingArea<DB, Shift>, Vec<DrawingArea<DB, Shift>>) {
+    if count == 0 {
+        return (root.clone(), vec![]);
+    }
+    let height = root.dim_in_pixel().1 as f64;
+    let ratio = LOWER_PANE_RATIO.min((1.0 - MIN_PRICE_PANE_RATIO) / count as f64);
+    let lower_height = (height * ratio) as u32;
+    let price_height = (height as u32)
+        .checked_sub(lower_height * count as u32 + X_LABEL_AREA)
+        .filter(|price_height| *price_height >= MIN_PRICE_PANE_HEIGHT);
+    let Some(price_height) = price_height else {
+        return (root.clone(), vec![]);
+    };
+
+    let (price_area, mut rest) = root.split_vertically(price_height);
+    let mut lower_areas = vec![];
+    for _ in 1..count {
+        let (area, below) = rest.split_vertically(lower_height);
+        lower_areas.push(area);
+        rest = below;
+    }
+    lower_areas.push(rest);
+    (price_area, lower_areas)
+}
+
+/// A chart on `area` with the time axis of `series`, so all panes line up horizontally.
+fn pane<'a, DB: DrawingBackend>(
+    area: &'a DrawingArea<DB, Shift>,
+    series: &Series,
+    spec: &ChartSpec,
+    y_axis: ValueAxis,
+    x_labels: bool,
+) -> Result<PaneChart<'a, DB>>
+where
+    DB::ErrorType: 'static,
+{
+    let x_axis = TimeAxis::new(series.bars(), spec.tz, area.dim_in_pixel().0);
+    Ok(ChartBuilder::on(area)
+        .x_label_area_size(if x_labels { X_LABEL_AREA } else { 0 })
+        .right_y_label_area_size(Y_LABEL_AREA)
+        .margin_top(PANE_MARGIN_TOP)
+        .build_cartesian_2d(x_axis, y_axis)?)
+}
+type PaneChart<'a, DB> = ChartContext<'a, DB, Cartesian2d<TimeAxis, ValueAxis>>;
+
+/// Axes and labels of a pane, the time labels only if `x_labels`, the values formatted by
+/// `y_formatter` or plotters' default.
+fn draw_mesh<DB: DrawingBackend>(
+    chart: &mut PaneChart<'_, DB>,
+    theme: &Theme,
+    x_labels: bool,
+    y_labels: usize,
+    y_formatter: Option<&dyn Fn(&f32) -> String>,
+) -> Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    let label_style = TextStyle::from(theme.font.into_font()).color(&theme.label);
+    let mut mesh = chart.configure_mesh();
+    mesh.disable_x_mesh()
+        .disable_y_mesh()
+        .axis_style(ShapeStyle {
+            color: theme.axis.to_rgba(),
+            filled: false,
+            stroke_width: 1,
+        })
+        .x_label_style(label_style.clone())
+        .y_labels(y_labels)
+        .y_label_style(label_style);
+    if !x_labels {
+        mesh.x_labels(0);
+    }
+    if let Some(y_formatter) = y_formatter {
+        mesh.y_label_formatter(y_formatter);
+    }
+    mesh.draw()?;
+    Ok(())
+}
+
+/// A dashed line between the last bar of a session and the first of the next one, except for
+/// Renko bricks, which do not follow the clock.
+fn draw_session_gaps<DB: DrawingBackend>(
+    chart: &mut PaneChart<'_, DB>,
+    spec: &ChartSpec,
+    series: &Series,
+    y_range: &Range<f32>,
+) -> Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    if spec.kind == ChartKind::Renko {
+        return Ok(());
+    }
+    let theme = spec.theme.theme();
+    for ix in session_gaps(series.bars()) {
+        let x = ix as f64 - 0.5;
+        chart.draw_series(DashedLineSeries::new(
+            [(x, y_range.start), (x, y_range.end)],
+            4,
+            4,
+            theme.gap.into(),
+        ))?;
+    }
+    Ok(())
+}
+
+fn draw_price_pane<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    window: &Window,
+    asset_class: AssetClass,
+    spec: &ChartSpec,
+    x_labels: bool,
+) -> Result<ChartGeometry>
+where
+    DB::ErrorType: 'static,
+{
+    let bands = match spec.show_bands {
+        true => spec
+            .bands
+            .iter()
+            .map(|kind| {
+                let band = kind.compute(window.all.bars());
+                let band = Band {
+                    upper: window.clip(&band.upper),
+                    middle: window.clip(&band.middle),
+                    lower: window.clip(&band.lower),
+                };
+                (kind, band)
+            })
+            .collect(),
+        false => vec![],
+    };
+    let series = &window.series;
+    // the bands may reach beyond the highs and lows
+    let y_range = bands
+        .iter()
+        .flat_map(|(_, band)| band.upper.iter().chain(&band.lower).flatten())
+        .fold(
+            series
+                .price_range()
+                .ok_or_else(|| eyre!("No bars to chart"))?,
+            |range, value| range.start.min(*value)..range.end.max(*value),
+        );
+    // room for the swing markers and pattern labels beyond the extreme candles
+    let y_range = if spec.show_pivots || spec.show_patterns {
+        let margin = (y_range.end - y_range.start) * ANNOTATION_MARGIN;
+        y_range.start - margin..y_range.end + margin
+    } else {
+        y_range
+    };
+    let theme = spec.theme.theme();
+    // percentages from the first bar in view
+    let base = series.bars()[0].close;
+    let y_axis = ValueAxis::new(y_range.clone()).scaled(spec.scale, base);
+    let mut chart = pane(area, series, spec, y_axis.clone(), x_labels)?;
+    draw_mesh(&mut chart, theme, x_labels, 10, None)?;
+    draw_session_gaps(&mut chart, spec, series, &y_range)?;
+    draw_bands(&mut chart, theme, &bands)?;
+    if spec.show_zones {
+        draw_zones(&mut chart, theme, series, spec.swing_strength)?;
+    }
+
+    draw_bars(&mut chart, theme, window, spec.kind, &y_range)?;
+
+    if spec.show_pivots {
+        draw_pivots(
+            &mut chart,
+            theme,
+            window,
+            asset_class,
+            spec.swing_strength,
+            &y_range,
+        )?;
+    }
+
+    if spec.show_patterns {
+        draw_patterns(&mut chart, theme, window)?;
+    }
+
+    let show_moving_averages = spec.show_moving_averages && !spec.moving_averages.is_empty();
+    if show_moving_averages {
+        draw_moving_averages(&mut chart, theme, window, &spec.moving_averages)?;
+    }
+    if show_moving_averages || !bands.is_empty() {
+        draw_legend(&mut chart, theme)?;
+    }
+
+    let (plot_x, price_y) = chart.plotting_area().get_pixel_range();
+    let bar_x = (0..series.bars().len())
+        .map(|ix| chart.backend_coord(&(ix as f64, y_range.start)).0)
+        .collect();
+    let price_limit = (
+        chart.backend_coord(&(0.0, y_range.start)).1,
+        chart.backend_coord(&(0.0, y_range.end)).1,
+    );
+    Ok(ChartGeometry {
+        bar_x,
+        plot_x,
+        // the lower panes are filled in by the caller
+        plot_bottom: price_y.end,
+        price_y,
+        price_axis: y_axis,
+        price_limit,
+    })
+}
+
+/// Zones as translucent rectangles across the whole chart, colored by whether they are support
+/// or resistance to the last close.
+fn draw_zones<DB: DrawingBackend>(
+    chart: &mut PaneChart<'_, DB>,
+    theme: &Theme,
+    series: &Series,
+    strength: SwingStrength,
+) -> Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    let bars = series.bars();
+    let Some(last_close) = bars.last().map(|bar| bar.close) else {
+        return Ok(());
+    };
+    chart.draw_series(zones(bars, strength).into_iter().map(|zone| {
+        let color = if zone.range.end < last_close {
+            theme.up_body
+        } else if zone.range.start > last_close {
+            theme.down_body
+        } else {
+            theme.label
+        };
+        Rectangle::new(
+            [
+                (-1.0, zone.range.start),
+                (bars.len() as f64, zone.range.end),
+            ],
+            color.mix(0.18).filled(),
+        )
+    }))?;
+    Ok(())
+}
+
+/// Each band as a translucent area between its upper and lower line, with a thin middle line.
+fn draw_bands<DB: DrawingBackend>(
+    chart: &mut PaneChart<'_, DB>,
+    theme: &Theme,
+    bands: &[(&BandKind, Band)],
+) -> Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    let points = |values: &[Option<f32>]| {
+        values
+            .iter()
+            .enumerate()
+            .filter_map(|(ix, value)| value.map(|value| (ix as f64, value)))
+            .collect::<Vec<_>>()
+    };
+    for ((kind, band), color) in bands.iter().zip(theme.bands.iter().cycle()) {
+        let color = *color;
+        let upper = points(&band.upper);
+        let lower = points(&band.lower);
+        // upper line left to right, then the lower one back
+        let outline = upper
+            .iter()
+            .chain(lower.iter().rev())
+            .copied()
+            .collect::<Vec<_>>();
+        chart.draw_series([Polygon::new(outline, color.mix(0.12))])?;
+        chart.draw_series(LineSeries::new(points(&band.middle), color.mix(0.6)))?;
+        chart.draw_series(LineSeries::new(lower, color))?;
+        chart
+            .draw_series(LineSeries::new(upper, color))?
+            .label(kind.to_string())
+            .legend(move |(x, y)| {
+                Rectangle::new([(x, y - 4), (x + 20, y + 4)], color.mix(0.5).filled())
+            });
+    }
+    Ok(())
+}
+
+/// One line per moving average, starting once its period is filled.
+fn draw_moving_averages<DB: DrawingBackend>(
+    chart: &mut PaneChart<'_, DB>,
+    theme: &Theme,
+    window: &Window,
+    moving_averages: &[MovingAverage],
+) -> Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    let closes = window.all.closes();
+    for (moving_average, color) in moving_averages.iter().zip(theme.indicators.iter().cycle()) {
+        let color = *color;
+        let values = window.clip(&moving_average.compute(&closes));
+        let points = values
+            .iter()
+            .enumerate()
+            .filter_map(|(ix, value)| value.map(|value| (ix as f64, value)));
+        chart
+            .draw_series(LineSeries::new(points, color.stroke_width(2)))?
+            .label(moving_average.to_string())
+            .legend(move |(x, y)| PathElement::new([(x, y), (x + 20, y)], color.stroke_width(2)));
+    }
+    Ok(())
+}
+
+/// Names of the labelled series of the pane, in its upper left corner.
+fn draw_legend<'a, DB: DrawingBackend + 'a>(
+    chart: &mut PaneChart<'a, DB>,
+    theme: &Theme,
+) -> Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    chart
+        .configure_series_labels()
+        .position(SeriesLabelPosition::UpperLeft)
+        .background_style(theme.legend_bg.mix(0.8))
+        .border_style(theme.axis)
+        .label_font(TextStyle::from(theme.font.into_font()).color(&theme.label))
+        .draw()?;
+    Ok(())
+}
+
+/// Floor pivot levels across their session, labelled in the latest one in view, and a triangle
+/// pointing at every swing high and low.
+fn draw_pivots<DB: DrawingBackend>(
+    chart: &mut PaneChart<'_, DB>,
+    theme: &Theme,
+    window: &Window,
+    asset_class: AssetClass,
+    strength: SwingStrength,
+    y_range: &Range<f32>,
+) -> Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    let bars = window.all.bars();
+    let label_style = TextStyle::from(theme.font.into_font()).color(&theme.label);
+    // derived from the previous session, even if that is out of view
+    let visible_pivots = floor_pivots(bars, &sessions(bars, asset_class))
+        .into_iter()
+        .filter_map(|pivots| {
+            let start = pivots.bars.start.max(window.range.start);
+            let end = pivots.bars.end.min(window.range.end);
+            (start < end).then(|| FloorPivots {
+                bars: start - window.range.start..end - window.range.start,
+                ..pivots
+            })
+        })
+        .collect::<Vec<_>>();
+    for (ix, pivots) in visible_pivots.iter().enumerate() {
+        let levels = [("P", pivots.pivot, theme.label)]
+            .into_iter()
+            .chain(
+                ["R1", "R2", "R3"]
+                    .into_iter()
+                    .zip(pivots.resistances)
+                    .map(|(name, level)| (name, level, theme.down_body)),
+            )
+            .chain(
+                ["S1", "S2", "S3"]
+                    .into_iter()
+                    .zip(pivots.supports)
+                    .map(|(name, level)| (name, level, theme.up_body)),
+            )
+            // the outer levels are often far off, they would squash the candles
+            .filter(|(_, level, _)| y_range.contains(level));
+        let start = pivots.bars.start as f64 - 0.5;
+        let end = pivots.bars.end as f64 - 0.5;
+        let latest = ix == visible_pivots.len() - 1;
+        for (name, level, color) in levels {
+            chart.draw_series([PathElement::new(
+                [(start, level), (end, level)],
+                color.mix(0.6),
+            )])?;
+            if latest {
+                chart.draw_series([EmptyElement::at((start, level))
+                    + Text::new(name, (2, -16), label_style.clone())])?;
+            }
+        }
+    }
+
+    let visible_swings = swings(bars, strength)
+        .into_iter()
+        .filter_map(|swing| Some((window.visible_ix(swing.ix)?, swing)));
+    chart.draw_series(visible_swings.map(|(ix, swing)| {
+        // tip towards the bar, a few pixels off its high or low
+        let (tip, base) = match swing.kind {
+            SwingKind::High => (-4, -11),
+            SwingKind::Low => (4, 11),
+        };
+        EmptyElement::at((ix as f64, swing.price))
+            + Polygon::new(vec![(-4, base), (4, base), (0, tip)], theme.label.filled())
+    }))?;
+    Ok(())
+}
+
+/// Abbreviated pattern names, bullish ones stacked below the low of the completing candle, the
+/// others above its high.
+fn draw_patterns<DB: DrawingBackend>(
+    chart: &mut PaneChart<'_, DB>,
+    theme: &Theme,
+    window: &Window,
+) -> Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    // scanned with the bars before the window, the trend leading into it counts
+    let bars = window.all.bars();
+    let font = (theme.font.0, PATTERN_FONT_SIZE).into_font();
+    let line_height = PATTERN_FONT_SIZE as i32 + 1;
+    // labels already on the (below, above) side of the candle, matches come ordered by candle
+    let mut stacked = (None, 0, 0);
+    let mut labels = vec![];
+    for found in scan(bars) {
+        let Some(ix) = window.visible_ix(found.ix) else {
+            continue;
+        };
+        if stacked.0 != Some(found.ix) {
+            stacked = (Some(found.ix), 0, 0);
+        }
+        let bar = &bars[found.ix];
+        let (color, anchor, y, vpos) = match found.pattern.bias() {
+            Bias::Bullish => {
+                stacked.1 += 1;
+                let y = 4 + (stacked.1 - 1) * line_height;
+                (&theme.up_body, bar.low, y, VPos::Top)
+            }
+            bias => {
+                stacked.2 += 1;
+                let y = -4 - (stacked.2 - 1) * line_height;
+                let color = match bias {
+                    Bias::Bearish => &theme.down_body,
+                    _ => &theme.label,
+                };
+                (color, bar.high, y, VPos::Bottom)
+            }
+        };
+        let style = TextStyle::from(font.clone())
+            .color(color)
+            .pos(Pos::new(HPos::Center, vpos));
+        labels.push(
+            EmptyElement::at((ix as f64, anchor))
+                + Text::new(found.pattern.abbreviation(), (0, y), style),
+        );
+    }
+    chart.draw_series(labels)?;
+    Ok(())
+}
+
+/// The bars themselves, as `kind` shows them.
+fn draw_bars<DB: DrawingBackend>(
+    chart: &mut PaneChart<'_, DB>,
+    theme: &Theme,
+    window: &Window,
+    kind: ChartKind,
+    y_range: &Range<f32>,
+) -> Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    let bars = || window.series.bars().iter().enumerate();
+    let half_width = window.bar_half_width;
+    match kind {
+        ChartKind::Candles | ChartKind::HeikinAshi | ChartKind::Renko => {
+            // wicks first, so the bodies cover them
+            chart.draw_series(bars().map(|(ix, bar)| {
+                let (_, wick) = candle_colors(theme, bar);
+                PathElement::new([(ix as f64, bar.low), (ix as f64, bar.high)], wick)
+            }))?;
+            chart.draw_series(bars().map(|(ix, bar)| {
+                let (body, _) = candle_colors(theme, bar);
+                let x = ix as f64;
+                Rectangle::new(
+                    [(x - half_width, bar.open), (x + half_width, bar.close)],
+                    body.filled(),
+                )
+            }))?;
+        }
+        ChartKind::Ohlc => {
+            // high-low line, open tick to the left, close tick to the right
+            let lines = [
+                |x: f64, _: f64, bar: &Bar| [(x, bar.low), (x, bar.high)],
+                |x: f64, w: f64, bar: &Bar| [(x - w, bar.open), (x, bar.open)],
+                |x: f64, w: f64, bar: &Bar| [(x, bar.close), (x + w, bar.close)],
+            ];
+            for line in lines {
+                chart.draw_series(bars().map(|(ix, bar)| {
+                    let (color, _) = candle_colors(theme, bar);
+                    let line = line(ix as f64, half_width, bar);
+                    PathElement::new(line, color.stroke_width(2))
+                }))?;
+            }
+        }
+        ChartKind::Line => {
+            let closes = bars().map(|(ix, bar)| (ix as f64, bar.close));
+            chart.draw_series(LineSeries::new(closes, theme.price_line.stroke_width(2)))?;
+        }
+        ChartKind::Area => {
+            let closes = bars().map(|(ix, bar)| (ix as f64, bar.close));
+            chart.draw_series(
+                AreaSeries::new(closes, y_range.start, theme.price_line.mix(0.25))
+                    .border_style(theme.price_line.stroke_width(2)),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// (body, wick) colors of `bar`, depending on its direction.
+fn candle_colors(theme: &Theme, bar: &Bar) -> (RGBColor, RGBColor) {
+    if bar.close >= bar.open {
+        (theme.up_body, theme.up_wick)
+    } else {
+        (theme.down_body, theme.down_wick)
+    }
+}
+
+/// Volume histogram, each bar colored like the direction of its candle.
+fn draw_volume_pane<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    window: &Window,
+    spec: &ChartSpec,
+    x_labels: bool,
+) -> Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    let series = &window.series;
+    let y_range = 0.0..series.max_volume().unwrap_or_default() as f32 * 1.05;
+    let theme = spec.theme.theme();
+    let mut chart = pane(
+        area,
+        series,
+        spec,
+        ValueAxis::new(y_range.clone()),
+        x_labels,
+    )?;
+    draw_mesh(
+        &mut chart,
+        theme,
+        x_labels,
+        3,
+        Some(&|volume| compact(*volume)),
+    )?;
+    draw_session_gaps(&mut chart, spec, series, &y_range)?;
+
+    chart.draw_series(series.bars().iter().enumerate().map(|(ix, bar)| {
+        let (color, _) = candle_colors(theme, bar);
+        let x = ix as f64;
+        let volume = bar.volume.unwrap_or_default() as f32;
+        Rectangle::new(
+            [
+                (x - window.bar_half_width, 0.0),
+                (x + window.bar_half_width, volume),
+            ],
+            color.filled(),
+        )
+    }))?;
+    Ok(())
+}
+
+/// An oscillator with its guide lines, titled with its name and parameters.
+fn draw_oscillator_pane<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    window: &Window,
+    spec: &ChartSpec,
+    oscillator: Oscillator,
+    x_labels: bool,
+) -> Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    let theme = spec.theme.theme();
+    let series = &window.series;
+    let closes = window.all.closes();
+    // (y range, guide lines, lines, histogram), of the bars in view
+    let (y_range, guides, lines, histogram) = match oscillator {
+        Oscillator::Rsi => (
+            0.0..100.0,
+            vec![30.0, 70.0],
+            vec![window.clip(&rsi(&closes))],
+            None,
+        ),
+        Oscillator::Stochastic => {
+            let stochastic = stochastic(window.all.bars());
+            (
+                0.0..100.0,
+                vec![20.0, 80.0],
+                vec![window.clip(&stochastic.k), window.clip(&stochastic.d)],
+                None,
+            )
+        }
+        Oscillator::Macd => {
+            let macd = macd(&closes);
+            let macd = Macd {
+                line: window.clip(&macd.line),
+                signal: window.clip(&macd.signal),
+                histogram: window.clip(&macd.histogram),
+            };
+            let y_range = [&macd.line, &macd.signal, &macd.histogram]
+                .into_iter()
+                .flatten()
+                .flatten()
+                .fold(0.0..0.0, |range: Range<f32>, value| {
+                    range.start.min(*value)..range.end.max(*value)
+                });
+            // some room, so the extremes do not touch the neighbouring panes
+            let margin = (y_range.end - y_range.start) * 0.05;
+            (
+                y_range.start - margin..y_range.end + margin,
+                vec![0.0],
+                vec![macd.line, macd.signal],
+                Some(macd.histogram),
+            )
+        }
+    };
+
+    let mut chart = pane(
+        area,
+        series,
+        spec,
+        ValueAxis::new(y_range.clone()),
+        x_labels,
+    )?;
+    draw_mesh(&mut chart, theme, x_labels, 3, None)?;
+    draw_session_gaps(&mut chart, spec, series, &y_range)?;
+
+    let x_range = -1.0..series.bars().len() as f64;
+    for guide in guides {
+        chart.draw_series(DashedLineSeries::new(
+            [(x_range.start, guide), (x_range.end, guide)],
+            2,
+            4,
+            theme.axis.mix(0.5).into(),
+        ))?;
+    }
+    if let Some(histogram) = histogram {
+        chart.draw_series(histogram.iter().enumerate().filter_map(|(ix, value)| {
+            let value = (*value)?;
+            let color = if value >= 0.0 {
+                theme.up_body
+            } else {
+                theme.down_body
+            };
+            let x = ix as f64;
+            Some(Rectangle::new(
+                [
+                    (x - window.bar_half_width, 0.0),
+                    (x + window.bar_half_width, value),
+                ],
+                color.mix(0.6).filled(),
+            ))
+        }))?;
+    }
+    for (values, color) in lines.iter().zip(&theme.indicators) {
+        let points = values
+            .iter()
+            .enumerate()
+            .filter_map(|(ix, value)| value.map(|value| (ix as f64, value)));
+        chart.draw_series(LineSeries::new(points, color.stroke_width(2)))?;
+    }
+
+    let title_style = TextStyle::from(theme.font.into_font()).color(&theme.label);
+    area.draw_text(&oscillator.to_string(), &title_style, PANE_TITLE_OFFSET)?;
+    Ok(())
+}
+
+/// `1234567` as `1.2M`, for labels of large quantities.
+fn compact(value: f32) -> String {
+    match value.abs() {
+        v if v >= 1e9 => format!("{:.1}B", value / 1e9),
+        v if v >= 1e6 => format!("{:.1}M", value / 1e6),
+        v if v >= 1e3 => format!("{:.1}K", value / 1e3),
+        _ => format!("{value:.0}"),
+    }
+}
@@ -0,0 +1,247 @@
+use crate::chart::{
+    axis::{Scale, ValueAxis},
+    kind::ChartKind,
+    render::ChartGeometry,
+};
+use crate::data::bar::Bar;
+use crate::theme::Theme;
+use clap::ValueEnum;
+use color_eyre::{Result, eyre::eyre};
+use plotters::coord::ranged1d::{Ranged, ValueFormatter};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style},
+    symbols::Marker,
+    text::Line,
+    widgets::{
+        Widget,
+        canvas::{Canvas, Context, Line as CanvasLine},
+    },
+};
+use ratatui_image::picker::{Picker, ProtocolType};
+use serde::{Deserialize, Serialize};
+
+/// Rows of the price axis per label, at least.
+const ROWS_PER_LABEL: u16 = 3;
+/// Dots the canvas spans vertically; the [`ValueAxis`] places the prices within them, so the
+/// canvas follows its scale.
+const RESOLUTION: i32 = 10_000;
+/// Share of the room of a bar its body takes, the rest keeps the bars apart.
+const BODY_SHARE: f64 = 0.6;
+
+/// How the charts get on screen.
+#[derive(ValueEnum, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Renderer {
+    /// images where the terminal has a graphics protocol, text otherwise
+    #[default]
+    Auto,
+    /// images, via whatever protocol the terminal has, halfblocks at worst
+    Image,
+    /// braille candles and a text price axis
+    Text,
+}
+impl Renderer {
+    /// [`Renderer::Auto`] decided: halfblocks blur a chart beyond use, text reads better.
+    pub(crate) fn resolve(self, picker: &Picker) -> Self {
+        match self {
+            Renderer::Auto if picker.protocol_type() == ProtocolType::Halfblocks => Renderer::Text,
+            Renderer::Auto => Renderer::Image,
+            renderer => renderer,
+        }
+    }
+}
+
+/// The bars of a chart drawn in cells rather than pixels, for terminals without a graphics
+/// protocol: braille candles colored by direction and a price axis of text right of them.
+///
+/// Just the bars of the chart kind; overlays and lower panes need the resolution of an image.
+pub(crate) struct TextChart<'a> {
+    bars: &'a [Bar],
+    kind: ChartKind,
+    axis: ValueAxis,
+    theme: &'a Theme,
+}
+impl<'a> TextChart<'a> {
+    /// Of `bars` as charts of `kind` show them, i.e. [transformed](ChartKind::transform).
+    pub(crate) fn new(
+        bars: &'a [Bar],
+        kind: ChartKind,
+        scale: Scale,
+        theme: &'a Theme,
+    ) -> Result<Self> {
+        let first = bars.first().ok_or_else(|| eyre!("No bars to chart"))?;
+        let range = bars.iter().fold(first.low..first.high, |range, bar| {
+            range.start.min(bar.low)..range.end.max(bar.high)
+        });
+        // a flat range would map every price to the same nowhere
+        let range = match range.end - range.start {
+            0.0 => range.start - 0.5..range.end + 0.5,
+            _ => range,
+        };
+        Ok(Self {
+            bars,
+            kind,
+            // percentages from the first bar in view, like the image
+            axis: ValueAxis::new(range).scaled(scale, first.close),
+            theme,
+        })
+    }
+
+    /// Where the chart puts the bars within `area`, in pixels of cells of `font_size` as if it
+    /// was an image, so the crosshair finds them the same way.
+    pub(crate) fn geometry(&self, area: Rect, font_size: (u16, u16)) -> ChartGeometry {
+        let (plot, _) = self.areas(area);
+        let (font_width, font_height) = (font_size.0 as i32, font_size.1 as i32);
+        let plot_x =
+            (plot.x - area.x) as i32 * font_width..(plot.right() - area.x) as i32 * font_width;
+        let price_y =
+            (plot.y - area.y) as i32 * font_height..(plot.bottom() - area.y) as i32 * font_height;
+        let bar_width = (plot_x.end - plot_x.start) as f32 / self.bars.len() as f32;
+        ChartGeometry {
+            bar_x: (0..self.bars.len())
+                .map(|ix| plot_x.start + ((ix as f32 + 0.5) * bar_width) as i32)
+                .collect(),
+            plot_x,
+            plot_bottom: price_y.end,
+            price_limit: (price_y.end - 1, price_y.start),
+            price_y,
+            price_axis: self.axis.clone(),
+        }
+    }
+
+    /// The canvas and the price labels right of it.
+    fn areas(&self, area: Rect) -> (Rect, Rect) {
+        let width = self
+            .labels(area.height)
+            .iter()
+            .map(|(_, label)| label.len())
+            .max()
+            .unwrap_or_default();
+        let [plot, labels] =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Length(width as u16 + 1)])
+                .areas(area);
+        (plot, labels)
+    }
+
+    /// Round prices and their labels, as many as fit `rows`.
+    fn labels(&self, rows: u16) -> Vec<(f32, String)> {
+        let count = (rows / ROWS_PER_LABEL).max(2) as usize;
+        self.axis
+            .key_points(count)
+            .into_iter()
+            .map(|price| (price, self.axis.format_ext(&price)))
+            .collect()
+    }
+
+    fn draw_bar(&self, ctx: &mut Context<'_>, ix: usize, bar: &Bar, dots_per_bar: f64) {
+        let x = ix as f64;
+        let y = |price: f32| self.axis.map(&price, (0, RESOLUTION)) as f64;
+        let color = match bar.close >= bar.open {
+            true => self.theme.bullish,
+            false => self.theme.bearish,
+        };
+        // a dot column in bars
+        let dot = 1.0 / dots_per_bar;
+        let body = (dots_per_bar * BODY_SHARE).round().max(1.0) as i32;
+        ctx.draw(&CanvasLine::new(x, y(bar.low), x, y(bar.high), color));
+        match self.kind {
+            ChartKind::Ohlc => {
+                let tick = (body / 2).max(1) as f64 * dot;
+                ctx.draw(&CanvasLine::new(
+                    x - tick,
+                    y(bar.open),
+                    x,
+                    y(bar.open),
+                    color,
+                ));
+                ctx.draw(&CanvasLine::new(
+                    x,
+                    y(bar.close),
+                    x + tick,
+                    y(bar.close),
+                    color,
+                ));
+            }
+            // as thick as the room allows, one dot column next to the other around the wick
+            _ => {
+                for column in -(body - 1) / 2..=body / 2 {
+                    let x = x + column as f64 * dot;
+                    ctx.draw(&CanvasLine::new(x, y(bar.open), x, y(bar.close), color));
+                }
+            }
+        }
+    }
+}
+
+impl Widget for TextChart<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let (plot, labels) = self.areas(area);
+        // room for a plot column, and a label after its gap
+        if plot.width == 0 || labels.width < 2 || area.height == 0 {
+            return;
+        }
+        for (price, label) in self.labels(area.height) {
+            let row = self
+                .axis
+                .map(&price, (plot.bottom() as i32 - 1, plot.y as i32))
+                .clamp(labels.y as i32, labels.bottom() as i32 - 1);
+            let label_area = Rect::new(labels.x + 1, row as u16, labels.width.saturating_sub(1), 1);
+            Line::styled(label, Style::new().fg(self.theme.text)).render(label_area, buf);
+        }
+
+        // braille cells are two dots wide
+        let dots_per_bar = (plot.width as f64 * 2.0 / self.bars.len() as f64).max(1.0);
+        let price_line = self.theme.price_line;
+        let price_line = Color::Rgb(price_line.0, price_line.1, price_line.2);
+        Canvas::default()
+            .marker(Marker::Braille)
+            .x_bounds([-0.5, self.bars.len() as f64 - 0.5])
+            .y_bounds([0.0, RESOLUTION as f64])
+            .paint(|ctx| match self.kind {
+                // the closes, there is no filling below them in dots
+                ChartKind::Line | ChartKind::Area => {
+                    let y = |bar: &Bar| self.axis.map(&bar.close, (0, RESOLUTION)) as f64;
+                    for (ix, pair) in self.bars.windows(2).enumerate() {
+                        ctx.draw(&CanvasLine::new(
+                            ix as f64,
+                            y(&pair[0]),
+                            ix as f64 + 1.0,
+                            y(&pair[1]),
+                            price_line,
+                        ));
+                    }
+                }
+                _ => {
+                    for (ix, bar) in self.bars.iter().enumerate() {
+                        self.draw_bar(ctx, ix, bar, dots_per_bar);
+                    }
+                }
+            })
+            .render(plot, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::bar::test_support::bars;
+    use crate::theme::ThemeName;
+
+    #[test]
+    fn renders_into_tiny_areas() {
+        let bars = bars(&[(1.0, 3.0, 0.5, 2.0), (2.0, 2.5, 1.0, 1.5)]);
+        for (width, height) in [(0, 10), (1, 1), (3, 10), (10, 0), (10, 1), (40, 10)] {
+            let area = Rect::new(0, 0, width, height);
+            let chart = TextChart::new(
+                &bars,
+                ChartKind::Candles,
+                Scale::Linear,
+                ThemeName::Dark.theme(),
+            )
+            .unwrap();
+            chart.render(area, &mut Buffer::empty(area));
+        }
+    }
+}
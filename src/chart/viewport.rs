@@ -0,0 +1,148 @@
+use std::ops::Range;
+
+/// Fewest bars a zoomed in chart shows.
+const MIN_BARS: usize = 10;
+/// Zooming shows this many times fewer (in) or more (out) bars.
+const ZOOM_FACTOR: f32 = 1.5;
+/// Panning moves the window by this share of its bars.
+const PAN_SHARE: usize = 4;
+
+/// Window of the bars a chart shows.
+///
+/// Kept relative to the latest bar rather than as a first bar index, so it stays meaningful
+/// across symbols and timeframes of different lengths, see [`Viewport::window`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) struct Viewport {
+    /// bars right of the window, 0 to follow the latest one
+    offset: usize,
+    /// bars in the window, `None` for all of them
+    count: Option<usize>,
+}
+impl Viewport {
+    /// The visible bars of a series of `len` bars: the first visible one up to the last.
+    pub(crate) fn window(&self, len: usize) -> Range<usize> {
+        let count = self.count.unwrap_or(len).clamp(MIN_BARS.min(len), len);
+        let end = len.saturating_sub(self.offset).max(count);
+        end - count..end
+    }
+
+    /// Whether the window shows every bar.
+    pub(crate) fn is_all(&self) -> bool {
+        self.count.is_none()
+    }
+
+    /// Fewer bars, keeping the right edge of the window in place.
+    pub(crate) fn zoom_in(&mut self, len: usize) {
+        let count = self.window(len).len() as f32 / ZOOM_FACTOR;
+        self.set(len, self.offset, count.round() as usize);
+    }
+
+    /// More bars, keeping the right edge of the window in place until it hits the first bar.
+    pub(crate) fn zoom_out(&mut self, len: usize) {
+        let count = self.window(len).len() as f32 * ZOOM_FACTOR;
+        self.set(len, self.offset, count.round() as usize);
+    }
+
+    /// Towards older bars.
+    pub(crate) fn pan_left(&mut self, len: usize) {
+        let step = (self.window(len).len() / PAN_SHARE).max(1);
+        self.pan(len, step as isize);
+    }
+
+    /// Towards the latest bar.
+    pub(crate) fn pan_right(&mut self, len: usize) {
+        let step = (self.window(len).len() / PAN_SHARE).max(1);
+        self.pan(len, -(step as isize));
+    }
+
+    /// By `bars`, towards older ones if positive, e.g. while dragging the chart.
+    pub(crate) fn pan(&mut self, len: usize, bars: isize) {
+        let window = self.window(len);
+        self.set(len, self.offset.saturating_add_signed(bars), window.len());
+    }
+
+    /// Clamped to the bars there are, so equal windows compare (and cache) equal.
+    fn set(&mut self, len: usize, offset: usize, count: usize) {
+        let count = count.clamp(MIN_BARS.min(len), len);
+        *self = match count {
+            count if count >= len => Viewport::default(),
+            count => Viewport {
+                offset: offset.min(len - count),
+                count: Some(count),
+            },
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zoom_clamped() {
+        let mut viewport = Viewport::default();
+        assert_eq!(viewport.window(100), 0..100);
+        assert!(viewport.is_all());
+
+        // 100 / 1.5, then 67 / 1.5
+        viewport.zoom_in(100);
+        assert_eq!(viewport.window(100), 33..100);
+        viewport.zoom_in(100);
+        assert_eq!(viewport.window(100), 55..100);
+        for _ in 0..10 {
+            viewport.zoom_in(100);
+        }
+        assert_eq!(viewport.window(100), 90..100);
+
+        for _ in 0..10 {
+            viewport.zoom_out(100);
+        }
+        assert_eq!(viewport, Viewport::default());
+    }
+
+    #[test]
+    fn pan_clamped() {
+        let mut viewport = Viewport::default();
+        // every bar shown, nowhere to pan to
+        viewport.pan_left(100);
+        assert_eq!(viewport, Viewport::default());
+
+        for _ in 0..10 {
+            viewport.zoom_in(100);
+        }
+        // by a quarter of the 10 bars
+        viewport.pan_left(100);
+        assert_eq!(viewport.window(100), 88..98);
+        viewport.pan(100, 1000);
+        assert_eq!(viewport.window(100), 0..10);
+        viewport.pan_right(100);
+        assert_eq!(viewport.window(100), 2..12);
+        viewport.pan(100, -1000);
+        assert_eq!(viewport.window(100), 90..100);
+        viewport.pan_right(100);
+        assert_eq!(viewport.window(100), 90..100);
+    }
+
+    #[test]
+    fn fewer_bars_than_the_window() {
+        let mut viewport = Viewport::default();
+        for _ in 0..2 {
+            viewport.zoom_in(100);
+        }
+        viewport.pan(100, 40);
+        assert_eq!(viewport.window(100), 15..60);
+
+        // the window stops at the first bar, then shrinks to the bars there are
+        assert_eq!(viewport.window(50), 0..45);
+        assert_eq!(viewport.window(20), 0..20);
+        assert_eq!(viewport.window(0), 0..0);
+
+        // fewer bars than a zoomed in window shows
+        viewport.zoom_in(5);
+        assert_eq!(viewport, Viewport::default());
+        viewport.zoom_out(0);
+        viewport.pan_left(0);
+        viewport.pan_right(0);
+        assert_eq!(viewport.window(0), 0..0);
+    }
+}
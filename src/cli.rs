@@ -0,0 +1,51 @@
+use crate::chart::export::PixelSize;
+use crate::config::Config;
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+/// Price action charts of a list of instruments, in the terminal.
+///
+/// Every option can also be set by its `CHART_*` environment variable or in the config file, see
+/// the README.
+#[derive(Parser)]
+#[command(version, about)]
+pub(crate) struct Cli {
+    #[command(subcommand)]
+    pub(crate) command: Option<Command>,
+    /// Config file to read instead of `$XDG_CONFIG_HOME/ratatui_plotters_image_demo/config.toml`
+    #[arg(long, env = "CHART_CONFIG", global = true)]
+    pub(crate) config: Option<PathBuf>,
+    /// Print the effective settings in the format of the config file, and exit
+    #[arg(long)]
+    pub(crate) print_config: bool,
+    #[command(flatten)]
+    pub(crate) settings: Config,
+}
+
+/// Instead of the terminal ui.
+#[derive(Subcommand)]
+pub(crate) enum Command {
+    /// Write the chart of a symbol to `<symbol>_<timeframe>.png` and `.svg`
+    Export {
+        symbol: String,
+        /// Width and height of the images in pixels, `--export-size` if not given
+        #[arg(long)]
+        size: Option<PixelSize>,
+        /// Directory to write the images to
+        #[arg(long, default_value = ".")]
+        out: PathBuf,
+    },
+    /// Write the chart of every symbol to `<symbol>_<timeframe>.png`, and all of them side by
+    /// side to `contact_sheet_<timeframe>.png`
+    Render {
+        /// Comma separated, e.g. `AAPL,NVDA`; all of them if not given
+        #[arg(long, value_delimiter = ',')]
+        symbols: Vec<String>,
+        /// Width and height of every chart in pixels, `--export-size` if not given
+        #[arg(long)]
+        size: Option<PixelSize>,
+        /// Directory to write the images to, created if missing
+        #[arg(long, default_value = ".")]
+        out: PathBuf,
+    },
+}
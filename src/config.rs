@@ -0,0 +1,278 @@
+use crate::View;
+use crate::chart::{export::PixelSize, kind::BoxSize, text::Renderer};
+use crate::data::resample::Timeframe;
+use crate::indicators::{bands::BandKind, moving_average::MovingAverage, pivots::SwingStrength};
+use crate::keymap::Keymap;
+use crate::theme::ThemeName;
+use crate::views::{
+    instruments::{CursorCommand, InstrumentCommand},
+    notes::{EditorCommand, NotesCommand},
+};
+use chrono_tz::Tz;
+use clap::{Args, ValueEnum};
+use color_eyre::{
+    Result,
+    eyre::{Ok, WrapErr, eyre},
+};
+use ratatui_image::picker::{Picker, ProtocolType};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Name of the config file in the config directory of the app.
+const CONFIG_FILE: &str = "config.toml";
+
+/// The settings of the app, each of them an option on the command line, an environment variable
+/// and a key of the config file, in that precedence; the keys are bound in the file only.
+///
+/// Unset settings are `None`; [`Config::defaults`] has what they fall back to.
+#[derive(Args, Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub(crate) struct Config {
+    /// Directory of `<SYMBOL>.csv` files to chart instead of the built-in fixtures
+    #[arg(long, env = "CHART_DATA_DIR", global = true)]
+    pub(crate) data_dir: Option<PathBuf>,
+    /// View shown at start
+    #[arg(long, value_enum, env = "CHART_VIEW")]
+    pub(crate) view: Option<View>,
+    /// Instrument selected at start, the first one if not given
+    #[arg(long, env = "CHART_SYMBOL")]
+    pub(crate) symbol: Option<String>,
+    /// Colors of the views and charts
+    #[arg(long, value_enum, env = "CHART_THEME", global = true)]
+    pub(crate) theme: Option<ThemeName>,
+    /// Bar duration of the charts
+    #[arg(
+        long,
+        value_enum,
+        ignore_case = true,
+        env = "CHART_TIMEFRAME",
+        global = true
+    )]
+    pub(crate) timeframe: Option<Timeframe>,
+    /// IANA timezone the chart times are shown in, e.g. `America/New_York`
+    #[arg(long, env = "CHART_TZ", global = true)]
+    pub(crate) tz: Option<TimeZone>,
+    /// Comma separated moving averages drawn over the candles, e.g. `sma:20,ema:50`
+    #[arg(
+        long,
+        value_delimiter = ',',
+        env = "CHART_MOVING_AVERAGES",
+        global = true
+    )]
+    pub(crate) moving_averages: Option<Vec<MovingAverage>>,
+    /// Comma separated bands drawn behind the candles, e.g. `bb:20:2,kc:20:1.5`
+    #[arg(long, value_delimiter = ',', env = "CHART_BANDS", global = true)]
+    pub(crate) bands: Option<Vec<BandKind>>,
+    /// Bars left and right of a swing high or low, e.g. `5:2`
+    #[arg(long, env = "CHART_SWING_STRENGTH", global = true)]
+    pub(crate) swing_strength: Option<SwingStrength>,
+    /// Price size of the Renko bricks, or `atr` to size them by the average true range
+    #[arg(long, env = "CHART_RENKO_BOX", global = true)]
+    pub(crate) renko_box: Option<BoxSize>,
+    /// How to draw the charts: images, or braille text where the terminal shows no images
+    #[arg(long, value_enum, env = "CHART_RENDERER")]
+    pub(crate) renderer: Option<Renderer>,
+    /// Graphics protocol for the chart images instead of the one the terminal answers with
+    #[arg(long, value_enum, env = "CHART_PROTOCOL")]
+    pub(crate) protocol: Option<Protocol>,
+    /// Width and height of a terminal cell in pixels, e.g. `10x20`, for terminals that do not
+    /// tell theirs
+    #[arg(long, env = "CHART_FONT_SIZE")]
+    pub(crate) font_size: Option<PixelSize>,
+    /// Width and height in pixels of the exported charts
+    #[arg(long, env = "CHART_EXPORT_SIZE", global = true)]
+    pub(crate) export_size: Option<PixelSize>,
+    #[arg(skip)]
+    pub(crate) keys: KeyConfig,
+}
+impl Config {
+    /// What unset settings fall back to; the data directory, symbol, protocol and font size stay
+    /// unset, for the built-in fixtures, the first instrument and whatever the terminal answers.
+    pub(crate) fn defaults() -> Self {
+        Self {
+            view: Some(View::default()),
+            theme: Some(ThemeName::default()),
+            timeframe: Some(Timeframe::default()),
+            tz: Some(TimeZone(Tz::UTC)),
+            moving_averages: Some(MovingAverage::defaults()),
+            bands: Some(BandKind::defaults()),
+            swing_strength: Some(SwingStrength::default()),
+            renko_box: Some(BoxSize::default()),
+            renderer: Some(Renderer::default()),
+            export_size: Some(PixelSize::default()),
+            keys: KeyConfig::defaults(),
+            ..Self::default()
+        }
+    }
+
+    /// The settings of the config file at `path`, or of
+    /// `$XDG_CONFIG_HOME/ratatui_plotters_image_demo/config.toml` if there is none, which may be
+    /// missing.
+    pub(crate) fn from_file(path: Option<&Path>) -> Result<Self> {
+        let (path, required) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match config_dir() {
+                Some(dir) => (dir.join(CONFIG_FILE), false),
+                None => return Ok(Self::default()),
+            },
+        };
+        let text = match std::fs::read_to_string(&path) {
+            std::result::Result::Ok(text) => text,
+            Err(err) if !required && err.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Self::default());
+            }
+            Err(err) => {
+                return Err(err).wrap_err_with(|| format!("Reading {}", path.display()));
+            }
+        };
+        toml::from_str(&text).wrap_err_with(|| format!("Invalid config file {}", path.display()))
+    }
+
+    /// `self`, with the settings it lacks taken from `fallback`.
+    pub(crate) fn or(self, fallback: Self) -> Self {
+        Self {
+            data_dir: self.data_dir.or(fallback.data_dir),
+            view: self.view.or(fallback.view),
+            symbol: self.symbol.or(fallback.symbol),
+            theme: self.theme.or(fallback.theme),
+            timeframe: self.timeframe.or(fallback.timeframe),
+            tz: self.tz.or(fallback.tz),
+            moving_averages: self.moving_averages.or(fallback.moving_averages),
+            bands: self.bands.or(fallback.bands),
+            swing_strength: self.swing_strength.or(fallback.swing_strength),
+            renko_box: self.renko_box.or(fallback.renko_box),
+            renderer: self.renderer.or(fallback.renderer),
+            protocol: self.protocol.or(fallback.protocol),
+            font_size: self.font_size.or(fallback.font_size),
+            export_size: self.export_size.or(fallback.export_size),
+            keys: self.keys.or(fallback.keys),
+        }
+    }
+
+    /// Queries the terminal for its graphics protocol and font size, overridden by the settings.
+    ///
+    /// Blocking, and to be called after entering the alternate screen, like
+    /// [`Picker::from_query_stdio`].
+    pub(crate) fn picker(&self) -> Result<Picker> {
+        let mut picker = match self.font_size {
+            Some(size) => {
+                let font_size = (
+                    size.width.try_into().wrap_err("Font width too large")?,
+                    size.height.try_into().wrap_err("Font height too large")?,
+                );
+                // terminals that do not tell their font size may still tell their protocol
+                let protocol = Picker::from_query_stdio()
+                    .map(|picker| picker.protocol_type())
+                    .ok();
+                let mut picker = Picker::from_fontsize(font_size);
+                if let Some(protocol) = protocol {
+                    picker.set_protocol_type(protocol);
+                }
+                picker
+            }
+            None => Picker::from_query_stdio()?,
+        };
+        if let Some(protocol) = self.protocol {
+            picker.set_protocol_type(protocol.into());
+        }
+        Ok(picker)
+    }
+}
+
+/// The keymap of every view, the `[keys]` table of the config file.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub(crate) struct KeyConfig {
+    pub(crate) instruments: Keymap<InstrumentCommand>,
+    /// while the crosshair is on the chart
+    pub(crate) crosshair: Keymap<CursorCommand>,
+    pub(crate) notes: Keymap<NotesCommand>,
+    /// while writing a note
+    pub(crate) note_editor: Keymap<EditorCommand>,
+}
+impl KeyConfig {
+    fn defaults() -> Self {
+        Self {
+            instruments: Keymap::defaults(),
+            crosshair: Keymap::defaults(),
+            notes: Keymap::defaults(),
+            note_editor: Keymap::defaults(),
+        }
+    }
+
+    /// `self`, view by view [or](Keymap::or) `fallback`.
+    fn or(self, fallback: Self) -> Self {
+        Self {
+            instruments: self.instruments.or(fallback.instruments),
+            crosshair: self.crosshair.or(fallback.crosshair),
+            notes: self.notes.or(fallback.notes),
+            note_editor: self.note_editor.or(fallback.note_editor),
+        }
+    }
+}
+
+/// The graphics protocols a terminal may draw images with.
+#[derive(ValueEnum, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Protocol {
+    /// unicode half blocks, two colored pixels per cell; any terminal
+    Halfblocks,
+    Sixel,
+    Kitty,
+    Iterm2,
+}
+impl From<Protocol> for ProtocolType {
+    fn from(protocol: Protocol) -> Self {
+        match protocol {
+            Protocol::Halfblocks => ProtocolType::Halfblocks,
+            Protocol::Sixel => ProtocolType::Sixel,
+            Protocol::Kitty => ProtocolType::Kitty,
+            Protocol::Iterm2 => ProtocolType::Iterm2,
+        }
+    }
+}
+
+/// An IANA timezone, e.g. `America/New_York`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(try_from = "String", into = "String")]
+pub(crate) struct TimeZone(pub(crate) Tz);
+impl FromStr for TimeZone {
+    type Err = color_eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        s.trim()
+            .parse()
+            .map(TimeZone)
+            .map_err(|err| eyre!("{err}, expected an IANA timezone, e.g. America/New_York"))
+    }
+}
+impl fmt::Display for TimeZone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0.name())
+    }
+}
+// as `America/New_York` in the config file too
+impl TryFrom<String> for TimeZone {
+    type Error = color_eyre::Report;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+impl From<TimeZone> for String {
+    fn from(tz: TimeZone) -> Self {
+        tz.to_string()
+    }
+}
+
+/// `$XDG_CONFIG_HOME/ratatui_plotters_image_demo`, or `~/.config/ratatui_plotters_image_demo`
+/// if that is not set.
+fn config_dir() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join(env!("CARGO_PKG_NAME")))
+}
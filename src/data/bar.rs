@@ -0,0 +1,123 @@
+use chrono::{DateTime, Utc};
+use std::ops::Range;
+
+/// One OHLC(V) candle.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Bar {
+    /// opening time of the bar
+    pub(crate) time: DateTime<Utc>,
+    pub(crate) open: f32,
+    pub(crate) high: f32,
+    pub(crate) low: f32,
+    pub(crate) close: f32,
+    pub(crate) volume: Option<f64>,
+    pub(crate) trades: Option<u64>,
+}
+impl Bar {
+    /// A bar without volume or trade count, e.g. a Renko brick.
+    pub(crate) fn new(time: DateTime<Utc>, open: f32, high: f32, low: f32, close: f32) -> Self {
+        Self {
+            time,
+            open,
+            high,
+            low,
+            close,
+            volume: None,
+            trades: None,
+        }
+    }
+}
+
+/// Bars of one instrument, oldest first.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Series {
+    bars: Vec<Bar>,
+}
+impl Series {
+    pub(crate) fn new(bars: Vec<Bar>) -> Self {
+        Self { bars }
+    }
+
+    pub(crate) fn bars(&self) -> &[Bar] {
+        &self.bars
+    }
+
+    /// Keeps only the bars opening within `range`.
+    pub(crate) fn within(mut self, range: &Range<DateTime<Utc>>) -> Self {
+        self.bars.retain(|bar| range.contains(&bar.time));
+        self
+    }
+
+    /// Closing prices, the input of most indicators.
+    pub(crate) fn closes(&self) -> Vec<f32> {
+        self.bars.iter().map(|bar| bar.close).collect()
+    }
+
+    /// Whether any bar has a volume.
+    pub(crate) fn has_volume(&self) -> bool {
+        self.bars.iter().any(|bar| bar.volume.is_some())
+    }
+
+    /// Largest volume of any bar, `None` without volume data.
+    pub(crate) fn max_volume(&self) -> Option<f64> {
+        self.bars
+            .iter()
+            .filter_map(|bar| bar.volume)
+            .reduce(f64::max)
+    }
+
+    /// Lowest low to highest high, `None` for an empty series.
+    pub(crate) fn price_range(&self) -> Option<Range<f32>> {
+        let first = self.bars.first()?;
+        Some(
+            self.bars
+                .iter()
+                .skip(1)
+                .fold(first.low..first.high, |range, bar| {
+                    range.start.min(bar.low)..range.end.max(bar.high)
+                }),
+        )
+    }
+}
+
+/// Bars and checks shared by the tests of whatever is computed from bars.
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::Bar;
+    use chrono::{DateTime, Utc};
+
+    /// The time `rfc3339`, e.g. `2024-01-09T23:00:00Z`.
+    pub(crate) fn time(rfc3339: &str) -> DateTime<Utc> {
+        rfc3339.parse().expect("valid RFC 3339 time")
+    }
+
+    /// A bar opening at `rfc3339` with `price` as its only price.
+    pub(crate) fn bar(rfc3339: &str, price: f32) -> Bar {
+        Bar::new(time(rfc3339), price, price, price, price)
+    }
+
+    /// Hourly bars from `(open, high, low, close)`, the first one at the unix epoch.
+    pub(crate) fn bars(prices: &[(f32, f32, f32, f32)]) -> Vec<Bar> {
+        prices
+            .iter()
+            .enumerate()
+            .map(|(ix, (open, high, low, close))| {
+                let time = DateTime::from_timestamp(ix as i64 * 3600, 0).expect("valid time");
+                Bar::new(time, *open, *high, *low, *close)
+            })
+            .collect()
+    }
+
+    /// Whether `values` are `expected`, missing where expected, and within rounding otherwise.
+    pub(crate) fn assert_close(values: &[Option<f32>], expected: &[Option<f32>]) {
+        assert_eq!(values.len(), expected.len(), "{values:?}");
+        for (value, expected) in values.iter().zip(expected) {
+            match (value, expected) {
+                (Some(value), Some(expected)) => {
+                    assert!((value - expected).abs() < 1e-3, "{values:?}")
+                }
+                (value, expected) => assert_eq!(value, expected, "{values:?}"),
+            }
+        }
+    }
+}
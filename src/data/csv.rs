@@ -0,0 +1,301 @@
+use crate::data::bar::{Bar, Series};
+use crate::data::data::AssetClass;
+use crate::data::provider::{DataProvider, Instrument};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, bail, eyre},
+};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::UNIX_EPOCH;
+
+/// Header comment declaring the asset class inside a csv file, e.g. `# asset_class: Crypto`.
+const ASSET_CLASS_HEADER: &str = "asset_class:";
+/// Extension of the sidecar file holding the asset class, e.g. `AAPL.asset_class` next to `AAPL.csv`.
+const ASSET_CLASS_SIDECAR: &str = "asset_class";
+
+/// [`DataProvider`] reading one `<SYMBOL>.csv` file per instrument from a directory.
+///
+/// Rows are `time,open,high,low,close[,volume[,trades]]` with `time` in unix seconds or RFC 3339,
+/// an optional `time,open,...` header line and `#` comment lines. The asset class comes either from a
+/// `# asset_class: <class>` comment or from a `<SYMBOL>.asset_class` sidecar file.
+pub(crate) struct CsvProvider {
+    dir: PathBuf,
+}
+impl CsvProvider {
+    pub(crate) fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn path(&self, symbol: &str) -> PathBuf {
+        // not `with_extension`, symbols like `BRK.B` have dots of their own
+        self.dir.join(format!("{symbol}.csv"))
+    }
+
+    async fn asset_class(&self, path: &Path) -> Result<AssetClass> {
+        let content = tokio::fs::read_to_string(path)
+            .await
+            .wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+        if let Some(class) = content
+            .lines()
+            .map_while(|line| line.trim().strip_prefix('#'))
+            .find_map(|comment| comment.trim().strip_prefix(ASSET_CLASS_HEADER))
+        {
+            return parse_asset_class(class, path);
+        }
+
+        let sidecar = path.with_extension(ASSET_CLASS_SIDECAR);
+        match tokio::fs::read_to_string(&sidecar).await {
+            std::result::Result::Ok(class) => parse_asset_class(&class, &sidecar),
+            Err(_) => bail!(
+                "{}: no '# {ASSET_CLASS_HEADER}' header and no {} sidecar",
+                path.display(),
+                sidecar.display()
+            ),
+        }
+    }
+}
+
+#[async_trait]
+impl DataProvider for CsvProvider {
+    async fn instruments(&self) -> Result<Vec<Instrument>> {
+        let mut entries = tokio::fs::read_dir(&self.dir)
+            .await
+            .wrap_err_with(|| format!("Failed to list {}", self.dir.display()))?;
+        let mut instruments = vec![];
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "csv") {
+                continue;
+            }
+            let Some(symbol) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            instruments.push(Instrument {
+                symbol: symbol.to_string(),
+                asset_class: self.asset_class(&path).await?,
+            });
+        }
+        instruments.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+        Ok(instruments)
+    }
+
+    async fn bars(&self, symbol: &str, range: Range<DateTime<Utc>>) -> Result<Series> {
+        let path = self.path(symbol);
+        let content = tokio::fs::read_to_string(&path)
+            .await
+            .wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+        let bars = parse_bars(&content).wrap_err_with(|| format!("In {}", path.display()))?;
+        Ok(Series::new(bars).within(&range))
+    }
+
+    /// The modification time of the file, in nanoseconds since the unix epoch.
+    async fn revision(&self, symbol: &str) -> Result<u64> {
+        let path = self.path(symbol);
+        let modified = tokio::fs::metadata(&path)
+            .await
+            .and_then(|metadata| metadata.modified())
+            .wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+        let since_epoch = modified.duration_since(UNIX_EPOCH).unwrap_or_default();
+        Ok(since_epoch.as_nanos() as u64)
+    }
+}
+
+fn parse_asset_class(class: &str, path: &Path) -> Result<AssetClass> {
+    AssetClass::from_str(class.trim())
+        .map_err(|_| eyre!("{}: unknown asset class '{}'", path.display(), class.trim()))
+}
+
+/// Parses and validates all rows, reporting the first bad one with its line number.
+fn parse_bars(content: &str) -> Result<Vec<Bar>> {
+    let mut bars: Vec<Bar> = vec![];
+    for (ix, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with("time") {
+            continue;
+        }
+        let bar = parse_row(line).wrap_err_with(|| format!("line {}: '{line}'", ix + 1))?;
+        if let Some(previous) = bars.last()
+            && bar.time <= previous.time
+        {
+            bail!(
+                "line {}: time {} is not after the previous one ({})",
+                ix + 1,
+                bar.time,
+                previous.time
+            );
+        }
+        bars.push(bar);
+    }
+    Ok(bars)
+}
+
+fn parse_row(line: &str) -> Result<Bar> {
+    let fields = line.split(',').map(str::trim).collect::<Vec<_>>();
+    if !(5..=7).contains(&fields.len()) {
+        bail!(
+            "expected 5 to 7 columns (time,open,high,low,close[,volume[,trades]]), found {}",
+            fields.len()
+        );
+    }
+    let time = parse_time(fields[0])?;
+    let price = |ix: usize, name: &str| {
+        fields[ix]
+            .parse::<f32>()
+            .wrap_err_with(|| format!("invalid {name} '{}'", fields[ix]))
+    };
+    let (open, high, low, close) = (
+        price(1, "open")?,
+        price(2, "high")?,
+        price(3, "low")?,
+        price(4, "close")?,
+    );
+    let volume = fields
+        .get(5)
+        .map(|volume| {
+            volume
+                .parse::<f64>()
+                .wrap_err_with(|| format!("invalid volume '{volume}'"))
+        })
+        .transpose()?;
+    let trades = fields
+        .get(6)
+        .map(|trades| {
+            trades
+                .parse::<u64>()
+                .wrap_err_with(|| format!("invalid trades '{trades}'"))
+        })
+        .transpose()?;
+    if !(low <= open.min(close) && open.max(close) <= high) {
+        bail!("inconsistent bar, expected low <= open/close <= high");
+    }
+    Ok(Bar {
+        volume,
+        trades,
+        ..Bar::new(time, open, high, low, close)
+    })
+}
+
+/// Unix seconds or RFC 3339.
+fn parse_time(field: &str) -> Result<DateTime<Utc>> {
+    match field.parse::<i64>() {
+        std::result::Result::Ok(secs) => {
+            DateTime::from_timestamp(secs, 0).ok_or_else(|| eyre!("time '{field}' out of range"))
+        }
+        Err(_) => DateTime::parse_from_rfc3339(field)
+            .map(|time| time.to_utc())
+            .wrap_err_with(|| format!("invalid time '{field}'")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::bar::test_support::time;
+
+    /// The error of `result` with its causes, outermost first.
+    fn error<T: std::fmt::Debug>(result: Result<T>) -> String {
+        let err = result.unwrap_err();
+        err.chain()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(": ")
+    }
+
+    #[test]
+    fn valid_file() {
+        let bars = parse_bars(
+            "# asset_class: Stock
+            time,open,high,low,close,volume,trades
+            1704067200,10,12,9,11
+            2024-01-01T01:00:00Z, 11, 13, 10.5, 12.5, 1500.5
+
+            2024-01-01T03:00:00+01:00,12.5,12.5,12,12,2000,42
+            ",
+        )
+        .unwrap();
+        assert_eq!(
+            bars,
+            [
+                Bar::new(time("2024-01-01T00:00:00Z"), 10.0, 12.0, 9.0, 11.0),
+                Bar {
+                    volume: Some(1500.5),
+                    ..Bar::new(time("2024-01-01T01:00:00Z"), 11.0, 13.0, 10.5, 12.5)
+                },
+                Bar {
+                    volume: Some(2000.0),
+                    trades: Some(42),
+                    ..Bar::new(time("2024-01-01T02:00:00Z"), 12.5, 12.5, 12.0, 12.0)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_rows() {
+        for (row, message) in [
+            (
+                "1704067200,10,12,9",
+                "expected 5 to 7 columns (time,open,high,low,close[,volume[,trades]]), found 4",
+            ),
+            (
+                "1704067200,10,12,9,11,1,2,3",
+                "expected 5 to 7 columns (time,open,high,low,close[,volume[,trades]]), found 8",
+            ),
+            ("yesterday,10,12,9,11", "invalid time 'yesterday'"),
+            (
+                "99999999999999,10,12,9,11",
+                "time '99999999999999' out of range",
+            ),
+            ("1704067200,ten,12,9,11", "invalid open 'ten'"),
+            ("1704067200,10,,9,11", "invalid high ''"),
+            ("1704067200,10,12,9,11,lots", "invalid volume 'lots'"),
+            ("1704067200,10,12,9,11,1,-2", "invalid trades '-2'"),
+        ] {
+            assert!(error(parse_row(row)).starts_with(message), "{row}");
+        }
+        for row in [
+            // high below the open, the close
+            "1704067200,10,9.5,9,9.5",
+            "1704067200,10,10.5,9,11",
+            // low above the open, the close
+            "1704067200,10,12,10.5,11",
+            "1704067200,10,12,9,8",
+            // high below low
+            "1704067200,10,9,12,11",
+        ] {
+            assert_eq!(
+                error(parse_row(row)),
+                "inconsistent bar, expected low <= open/close <= high",
+                "{row}"
+            );
+        }
+    }
+
+    #[test]
+    fn bad_rows_by_line() {
+        assert_eq!(
+            error(parse_bars(
+                "time,open,high,low,close\n1704067200,10,12,9,11\n1704070800,10,12,9,8"
+            )),
+            "line 3: '1704070800,10,12,9,8': inconsistent bar, expected low <= open/close <= high"
+        );
+    }
+
+    #[test]
+    fn times_in_order() {
+        assert_eq!(
+            error(parse_bars("1704070800,10,12,9,11\n1704067200,10,12,9,11")),
+            "line 2: time 2024-01-01 00:00:00 UTC is not after the previous one (2024-01-01 01:00:00 UTC)"
+        );
+        assert_eq!(
+            error(parse_bars(
+                "1704067200,10,12,9,11\n2024-01-01T00:00:00Z,10,12,9,11"
+            )),
+            "line 2: time 2024-01-01 00:00:00 UTC is not after the previous one (2024-01-01 00:00:00 UTC)"
+        );
+    }
+}
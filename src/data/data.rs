@@ -1,13 +1,23 @@
+use crate::data::bar::{Bar, Series};
+use crate::data::provider::{DataProvider, Instrument};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use color_eyre::{Result, eyre::eyre};
+use std::ops::Range;
+use std::str::FromStr;
+use strum::IntoEnumIterator;
 use strum_macros::{AsRefStr, Display, EnumIter, EnumString};
 
-#[derive(Display, AsRefStr, PartialEq)]
+#[derive(Display, AsRefStr, Clone, Copy, PartialEq, EnumString)]
+#[strum(ascii_case_insensitive)]
 pub(crate) enum AssetClass {
     Forex,
     Stock,
     Crypto,
 }
 
-#[derive(Display, AsRefStr, PartialEq, EnumIter, EnumString)]
+#[allow(clippy::upper_case_acronyms)] // tickers are spelled the way exchanges list them
+#[derive(Display, AsRefStr, Clone, Copy, PartialEq, Eq, Hash, EnumIter, EnumString)]
 pub(crate) enum Symbol {
     AAPL,
     AMD,
@@ -57,7 +67,56 @@ impl Symbol {
     }
 }
 
-pub(crate) fn get_data(symbol: Symbol) -> Vec<(u32, f32, f32, f32, f32)> {
+/// [`DataProvider`] serving the hardcoded fixtures below.
+pub(crate) struct StaticProvider;
+
+#[async_trait]
+impl DataProvider for StaticProvider {
+    async fn instruments(&self) -> Result<Vec<Instrument>> {
+        Ok(Symbol::iter()
+            .map(|v| Instrument {
+                symbol: v.to_string(),
+                asset_class: v.asset_class(),
+            })
+            .collect())
+    }
+
+    async fn bars(&self, symbol: &str, range: Range<DateTime<Utc>>) -> Result<Series> {
+        let symbol = Symbol::from_str(symbol)?;
+        let bars = get_data(symbol)
+            .into_iter()
+            .map(|(time, open, high, low, close)| {
+                let time = DateTime::from_timestamp(time.into(), 0)
+                    .ok_or_else(|| eyre!("Invalid timestamp {time} in {symbol} fixtures"))?;
+                Ok(Bar {
+                    volume: Some(fixture_volume(
+                        symbol.asset_class(),
+                        time,
+                        high - low,
+                        close,
+                    )),
+                    ..Bar::new(time, open, high, low, close)
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Series::new(bars).within(&range))
+    }
+}
+
+/// A made-up volume for a fixture bar, as fictitious as its prices: the wider the bar ranges, the
+/// more trades, with a wobble so equal ranges do not line up.
+fn fixture_volume(asset_class: AssetClass, time: DateTime<Utc>, range: f32, close: f32) -> f64 {
+    // units an hour, shares, lots or coins
+    let base = match asset_class {
+        AssetClass::Stock => 2_000_000.0,
+        AssetClass::Forex => 5_000.0,
+        AssetClass::Crypto => 800.0,
+    };
+    let wobble = 0.8 + 0.1 * (time.timestamp() / 3600 % 5) as f64;
+    base * wobble * (1.0 + 40.0 * (range / close) as f64)
+}
+
+fn get_data(symbol: Symbol) -> Vec<(u32, f32, f32, f32, f32)> {
     // time,open,high,low,close
     match symbol {
         Symbol::AAPL => vec![
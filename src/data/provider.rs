@@ -0,0 +1,42 @@
+use crate::data::{bar::Series, data::AssetClass};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use color_eyre::Result;
+use std::ops::Range;
+
+/// Covers every bar a provider can have.
+pub(crate) const ALL_TIME: Range<DateTime<Utc>> =
+    DateTime::<Utc>::MIN_UTC..DateTime::<Utc>::MAX_UTC;
+
+#[derive(Clone)]
+pub(crate) struct Instrument {
+    pub(crate) symbol: String,
+    pub(crate) asset_class: AssetClass,
+}
+impl Instrument {
+    pub(crate) fn symbol(&self) -> &str {
+        &self.symbol
+    }
+    pub(crate) fn asset_class(&self) -> &str {
+        self.asset_class.as_ref()
+    }
+}
+
+/// Source of market data for the views.
+///
+/// Implementations are shared between the ui and the background chart jobs,
+/// hence `Send + Sync`.
+#[async_trait]
+pub(crate) trait DataProvider: Send + Sync {
+    /// Instruments this provider has bars for, in display order.
+    async fn instruments(&self) -> Result<Vec<Instrument>>;
+
+    /// Bars of `symbol` opening within `range`.
+    async fn bars(&self, symbol: &str, range: Range<DateTime<Utc>>) -> Result<Series>;
+
+    /// Changes whenever the bars of `symbol` do, so that charts of the previous ones are not
+    /// shown from the cache. Providers whose bars never change keep the default.
+    async fn revision(&self, _symbol: &str) -> Result<u64> {
+        Ok(0)
+    }
+}
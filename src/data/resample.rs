@@ -0,0 +1,297 @@
+use crate::data::bar::{Bar, Series};
+use crate::data::data::AssetClass;
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
+use chrono_tz::Tz;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use strum_macros::Display;
+
+/// Bar duration the charts are drawn in. The source data is expected to be hourly (or finer).
+#[derive(
+    Display, ValueEnum, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash,
+)]
+pub(crate) enum Timeframe {
+    #[default]
+    #[strum(to_string = "1h")]
+    #[value(name = "1h")]
+    #[serde(rename = "1h")]
+    H1,
+    #[strum(to_string = "4h")]
+    #[value(name = "4h")]
+    #[serde(rename = "4h")]
+    H4,
+    #[strum(to_string = "1D")]
+    #[value(name = "1d")]
+    #[serde(rename = "1d", alias = "1D")]
+    D1,
+    #[strum(to_string = "1W")]
+    #[value(name = "1w")]
+    #[serde(rename = "1w", alias = "1W")]
+    W1,
+}
+impl Timeframe {
+    /// The next (longer) timeframe, wrapping around to the shortest.
+    pub(crate) fn next(&self) -> Self {
+        match self {
+            Timeframe::H1 => Timeframe::H4,
+            Timeframe::H4 => Timeframe::D1,
+            Timeframe::D1 => Timeframe::W1,
+            Timeframe::W1 => Timeframe::H1,
+        }
+    }
+}
+
+/// When the trading day of an asset class starts.
+struct Session {
+    /// timezone the session is defined in
+    tz: Tz,
+    /// added to the local time so that the trading day starts at midnight, e.g. forex
+    /// days roll over at 17:00 New York time
+    rollover_shift: TimeDelta,
+    /// intraday bars are aligned to this (shifted) time of day, e.g. the 09:30 stock open
+    anchor: NaiveTime,
+}
+impl Session {
+    fn of(asset_class: AssetClass) -> Self {
+        match asset_class {
+            // 24/7, days and weeks in UTC
+            AssetClass::Crypto => Session {
+                tz: Tz::UTC,
+                rollover_shift: TimeDelta::zero(),
+                anchor: NaiveTime::MIN,
+            },
+            // New York exchange hours, extended hours bars count to their calendar day
+            AssetClass::Stock => Session {
+                tz: Tz::America__New_York,
+                rollover_shift: TimeDelta::zero(),
+                anchor: NaiveTime::from_hms_opt(9, 30, 0).expect("valid time"),
+            },
+            // the week runs from sunday 17:00 to friday 17:00 New York time
+            AssetClass::Forex => Session {
+                tz: Tz::America__New_York,
+                rollover_shift: TimeDelta::hours(7),
+                anchor: NaiveTime::MIN,
+            },
+        }
+    }
+
+    /// Session local time of `bar`, shifted so that the trading day starts at midnight.
+    fn local(&self, bar: &Bar) -> NaiveDateTime {
+        bar.time.with_timezone(&self.tz).naive_local() + self.rollover_shift
+    }
+}
+
+/// What an aggregated bar is identified by.
+#[derive(PartialEq)]
+enum Bucket {
+    /// the session day too, so that no bar spans two of them
+    Intraday(NaiveDate, NaiveDateTime),
+    Day(NaiveDate),
+    Week(i32, u32),
+}
+
+/// Aggregates `series` into bars of `timeframe`, using the trading sessions of `asset_class`
+/// for day and week boundaries.
+///
+/// Each aggregated bar opens at the time of its first source bar.
+pub(crate) fn resample(series: Series, timeframe: Timeframe, asset_class: AssetClass) -> Series {
+    let session = Session::of(asset_class);
+    let bucket = |bar: &Bar| {
+        let local = session.local(bar);
+        match timeframe {
+            Timeframe::H1 | Timeframe::H4 => {
+                let hours = if timeframe == Timeframe::H1 { 1 } else { 4 };
+                let anchor = local.date().and_time(session.anchor);
+                let periods = (local - anchor).num_seconds().div_euclid(hours * 60 * 60);
+                Bucket::Intraday(local.date(), anchor + TimeDelta::hours(periods * hours))
+            }
+            Timeframe::D1 => Bucket::Day(local.date()),
+            Timeframe::W1 => Bucket::Week(local.iso_week().year(), local.iso_week().week()),
+        }
+    };
+
+    let bars = group(series.bars(), bucket)
+        .into_iter()
+        .map(|range| {
+            let mut bars = series.bars()[range].iter();
+            let first = bars.next().expect("groups are not empty").clone();
+            bars.fold(first, |mut into, bar| {
+                merge(&mut into, bar);
+                into
+            })
+        })
+        .collect();
+    Series::new(bars)
+}
+
+/// Index ranges of `bars` falling into the same trading day of `asset_class`.
+pub(crate) fn sessions(bars: &[Bar], asset_class: AssetClass) -> Vec<Range<usize>> {
+    let session = Session::of(asset_class);
+    group(bars, |bar| Bucket::Day(session.local(bar).date()))
+}
+
+/// Index ranges of consecutive bars sharing a bucket.
+fn group(bars: &[Bar], bucket: impl Fn(&Bar) -> Bucket) -> Vec<Range<usize>> {
+    let mut groups: Vec<Range<usize>> = vec![];
+    let mut current = None;
+    for (ix, bar) in bars.iter().enumerate() {
+        let bar_bucket = bucket(bar);
+        match groups.last_mut() {
+            Some(last) if current.as_ref() == Some(&bar_bucket) => last.end = ix + 1,
+            _ => {
+                groups.push(ix..ix + 1);
+                current = Some(bar_bucket);
+            }
+        }
+    }
+    groups
+}
+
+/// Extends the aggregated bar `into` by the later bar `bar`.
+fn merge(into: &mut Bar, bar: &Bar) {
+    into.high = into.high.max(bar.high);
+    into.low = into.low.min(bar.low);
+    into.close = bar.close;
+    into.volume = sum(into.volume, bar.volume);
+    into.trades = sum(into.trades, bar.trades);
+}
+
+/// Missing values count as zero, unless both are missing.
+fn sum<T: std::ops::Add<Output = T>>(a: Option<T>, b: Option<T>) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a + b),
+        (a, b) => a.or(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::bar::test_support::{bar, time};
+
+    /// Opening time and close of every bar of `bars` resampled.
+    fn resampled(
+        bars: &[Bar],
+        timeframe: Timeframe,
+        asset_class: AssetClass,
+    ) -> Vec<(String, f32)> {
+        resample(Series::new(bars.to_vec()), timeframe, asset_class)
+            .bars()
+            .iter()
+            .map(|bar| (bar.time.format("%m-%d %H:%M").to_string(), bar.close))
+            .collect()
+    }
+
+    #[test]
+    fn merges_ohlcv() {
+        let bars = vec![
+            Bar {
+                volume: Some(100.0),
+                ..Bar::new(time("2024-01-09T00:00:00Z"), 10.0, 12.0, 9.0, 11.0)
+            },
+            Bar {
+                volume: Some(50.0),
+                ..Bar::new(time("2024-01-09T01:00:00Z"), 11.0, 15.0, 10.0, 14.0)
+            },
+            // a missing volume adds nothing
+            Bar::new(time("2024-01-09T02:00:00Z"), 14.0, 14.0, 8.0, 9.0),
+        ];
+        let series = resample(Series::new(bars), Timeframe::D1, AssetClass::Crypto);
+        assert_eq!(
+            series.bars(),
+            [Bar {
+                volume: Some(150.0),
+                ..Bar::new(time("2024-01-09T00:00:00Z"), 10.0, 15.0, 8.0, 9.0)
+            }]
+        );
+    }
+
+    #[test]
+    fn days_of_asset_classes() {
+        // 18:00, 23:00 and 00:00 in New York
+        let bars = [
+            bar("2024-01-09T23:00:00Z", 1.0),
+            bar("2024-01-10T04:00:00Z", 2.0),
+            bar("2024-01-10T05:00:00Z", 3.0),
+        ];
+        // UTC days
+        assert_eq!(
+            resampled(&bars, Timeframe::D1, AssetClass::Crypto),
+            [("01-09 23:00".into(), 1.0), ("01-10 04:00".into(), 3.0)]
+        );
+        // New York days
+        assert_eq!(
+            resampled(&bars, Timeframe::D1, AssetClass::Stock),
+            [("01-09 23:00".into(), 2.0), ("01-10 05:00".into(), 3.0)]
+        );
+        assert_eq!(sessions(&bars, AssetClass::Stock), [0..2, 2..3]);
+
+        // 16:00 and 17:00 in New York, forex days roll over in between
+        let bars = [
+            bar("2024-01-09T21:00:00Z", 1.0),
+            bar("2024-01-09T22:00:00Z", 2.0),
+        ];
+        assert_eq!(
+            resampled(&bars, Timeframe::D1, AssetClass::Forex),
+            [("01-09 21:00".into(), 1.0), ("01-09 22:00".into(), 2.0)]
+        );
+        assert_eq!(
+            resampled(&bars, Timeframe::D1, AssetClass::Crypto),
+            [("01-09 21:00".into(), 2.0)]
+        );
+    }
+
+    #[test]
+    fn weeks_of_asset_classes() {
+        // sunday 16:00 and 17:00 in New York, when the forex week starts
+        let bars = [
+            bar("2024-01-14T21:00:00Z", 1.0),
+            bar("2024-01-14T22:00:00Z", 2.0),
+        ];
+        assert_eq!(
+            resampled(&bars, Timeframe::W1, AssetClass::Forex),
+            [("01-14 21:00".into(), 1.0), ("01-14 22:00".into(), 2.0)]
+        );
+        assert_eq!(
+            resampled(&bars, Timeframe::W1, AssetClass::Crypto),
+            [("01-14 21:00".into(), 2.0)]
+        );
+    }
+
+    #[test]
+    fn stock_hours_from_the_open() {
+        // 09:30, 12:30 and 13:30 in New York
+        let bars = [
+            bar("2024-01-09T14:30:00Z", 1.0),
+            bar("2024-01-09T17:30:00Z", 2.0),
+            bar("2024-01-09T18:30:00Z", 3.0),
+        ];
+        assert_eq!(
+            resampled(&bars, Timeframe::H4, AssetClass::Stock),
+            [("01-09 14:30".into(), 2.0), ("01-09 18:30".into(), 3.0)]
+        );
+        // UTC hours from midnight: 12:00 to 16:00, 16:00 to 20:00
+        assert_eq!(
+            resampled(&bars, Timeframe::H4, AssetClass::Crypto),
+            [("01-09 14:30".into(), 1.0), ("01-09 17:30".into(), 3.0)]
+        );
+    }
+
+    #[test]
+    fn hours_across_the_day_boundary() {
+        // 23:00 and 00:00 in New York, both of the 21:30 to 01:30 period, which the day splits
+        let bars = [
+            bar("2024-01-10T04:00:00Z", 1.0),
+            bar("2024-01-10T05:00:00Z", 2.0),
+        ];
+        assert_eq!(
+            resampled(&bars, Timeframe::H4, AssetClass::Stock),
+            [("01-10 04:00".into(), 1.0), ("01-10 05:00".into(), 2.0)]
+        );
+        assert_eq!(
+            resampled(&bars, Timeframe::D1, AssetClass::Stock),
+            [("01-10 04:00".into(), 1.0), ("01-10 05:00".into(), 2.0)]
+        );
+    }
+}
@@ -0,0 +1,203 @@
+use crate::HOTKEY_STYLE;
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::{Alignment, Position, Rect},
+    text::{Line, Span},
+};
+use std::ops::Range;
+
+/// A block title and the keys its [`HOTKEY_STYLE`] spans stand for, by column within the title.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct HotkeyLine {
+    pub(crate) line: Line<'static>,
+    keys: Vec<(Range<u16>, KeyCode)>,
+}
+impl HotkeyLine {
+    pub(crate) fn width(&self) -> u16 {
+        self.line.width() as u16
+    }
+
+    /// Appends `span`, not a key.
+    pub(crate) fn push(&mut self, span: impl Into<Span<'static>>) {
+        self.line.spans.push(span.into());
+    }
+
+    /// Appends `name` as a hotkey pressing `key`.
+    pub(crate) fn push_key(&mut self, name: String, key: KeyCode) {
+        let start = self.width();
+        self.push(Span::styled(name, HOTKEY_STYLE));
+        self.keys.push((start..self.width(), key));
+    }
+
+    /// Appends `other`, its keys shifted behind what is here already.
+    pub(crate) fn append(&mut self, other: HotkeyLine) {
+        let offset = self.width();
+        self.keys.extend(
+            other
+                .keys
+                .into_iter()
+                .map(|(columns, key)| (columns.start + offset..columns.end + offset, key)),
+        );
+        self.line.spans.extend(other.line.spans);
+    }
+
+    pub(crate) fn alignment(mut self, alignment: Alignment) -> Self {
+        self.line.alignment = Some(alignment);
+        self
+    }
+}
+impl From<Line<'static>> for HotkeyLine {
+    /// A title without hotkeys, which may still cover those of others.
+    fn from(line: Line<'static>) -> Self {
+        Self { line, keys: vec![] }
+    }
+}
+
+/// The hotkeys of the block titles as last rendered, so clicking a hotkey presses it.
+#[derive(Default)]
+pub(crate) struct HotkeyTitles {
+    /// cells of every hotkey on screen
+    keys: Vec<(u16, Range<u16>, KeyCode)>,
+}
+impl HotkeyTitles {
+    /// Forgets the titles of the previous frame.
+    pub(crate) fn clear(&mut self) {
+        self.keys.clear();
+    }
+
+    /// `titles`, all the top titles of a bordered block over `area`.
+    pub(crate) fn top(&mut self, area: Rect, titles: &[&HotkeyLine]) {
+        self.add(area, area.y, titles);
+    }
+
+    /// `titles`, all the bottom titles of a bordered block over `area`.
+    pub(crate) fn bottom(&mut self, area: Rect, titles: &[&HotkeyLine]) {
+        self.add(area, area.bottom().saturating_sub(1), titles);
+    }
+
+    /// Places `titles` the way the block does: between the corners, right aligned ones first,
+    /// then centered ones, then left aligned ones over them, each cut off where the room ends.
+    /// Keys cut off or drawn over are left out.
+    fn add(&mut self, area: Rect, row: u16, titles: &[&HotkeyLine]) {
+        let left = area.x + 1;
+        let right = area.right().saturating_sub(1).max(left);
+        let aligned = |alignment: Alignment| {
+            titles
+                .iter()
+                .filter(move |title| title.line.alignment.unwrap_or(Alignment::Left) == alignment)
+        };
+        let mut drawn = vec![];
+
+        let mut end = right;
+        for title in aligned(Alignment::Right).rev() {
+            if end <= left {
+                break;
+            }
+            let start = end.saturating_sub(title.width()).max(left);
+            cover(&mut drawn, title, start, end);
+            end = end.saturating_sub(title.width() + 1);
+        }
+
+        let total = aligned(Alignment::Center)
+            .map(|title| title.width() + 1)
+            .sum::<u16>()
+            .saturating_sub(1);
+        let mut start = left + (right - left).saturating_sub(total) / 2;
+        for title in aligned(Alignment::Center) {
+            cover(&mut drawn, title, start, right);
+            start = start.saturating_add(title.width() + 1);
+        }
+
+        let mut start = left;
+        for title in aligned(Alignment::Left) {
+            cover(&mut drawn, title, start, right);
+            start = start.saturating_add(title.width() + 1);
+        }
+
+        self.keys
+            .extend(drawn.into_iter().map(|(columns, key)| (row, columns, key)));
+    }
+
+    /// The key of the hotkey at `position`, if any.
+    pub(crate) fn key_at(&self, position: Position) -> Option<KeyCode> {
+        self.keys
+            .iter()
+            .find(|(row, columns, _)| *row == position.y && columns.contains(&position.x))
+            .map(|(_, _, key)| *key)
+    }
+}
+
+/// `title` drawn from column `start` on, cut off at `end`, over the keys `drawn` before it.
+fn cover(drawn: &mut Vec<(Range<u16>, KeyCode)>, title: &HotkeyLine, start: u16, end: u16) {
+    if start >= end {
+        return;
+    }
+    let columns = start..(start + title.width()).min(end);
+    drawn.retain(|(key, _)| key.end <= columns.start || columns.end <= key.start);
+    drawn.extend(
+        title
+            .keys
+            .iter()
+            .map(|(key, code)| (key.start + start..key.end + start, *code))
+            .filter(|(key, _)| key.end <= columns.end),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{buffer::Buffer, widgets::Block, widgets::Widget};
+
+    fn title(keys: &[char], alignment: Alignment) -> HotkeyLine {
+        let mut line = HotkeyLine::default();
+        for key in keys {
+            line.push_key(key.to_string(), KeyCode::Char(*key));
+            line.push("(key)");
+        }
+        line.alignment(alignment)
+    }
+
+    /// The keys registered for `titles` on the top row, checked against the cells drawn there.
+    fn drawn_keys(width: u16, titles: &[&HotkeyLine]) -> Vec<char> {
+        let area = Rect::new(0, 0, width, 3);
+        let mut buffer = Buffer::empty(area);
+        titles
+            .iter()
+            .fold(Block::bordered(), |block, title| {
+                block.title(title.line.clone())
+            })
+            .render(area, &mut buffer);
+        let mut hotkeys = HotkeyTitles::default();
+        hotkeys.top(area, titles);
+        hotkeys
+            .keys
+            .iter()
+            .map(|(row, columns, key)| {
+                let KeyCode::Char(c) = key else { panic!() };
+                let cell = &buffer[(columns.start, *row)];
+                assert_eq!(cell.symbol(), c.to_string());
+                assert_eq!(cell.style().add_modifier, HOTKEY_STYLE.add_modifier);
+                assert_eq!(
+                    hotkeys.key_at(Position::new(columns.start, *row)),
+                    Some(*key)
+                );
+                *c
+            })
+            .collect()
+    }
+
+    #[test]
+    fn keys_as_drawn() {
+        let left = title(&['a', 'b'], Alignment::Left);
+        let right = title(&['x', 'y'], Alignment::Right);
+        // 2 corners, 12 left, 12 right, and a space between them
+        let mut keys = drawn_keys(27, &[&left, &right]);
+        keys.sort();
+        assert_eq!(keys, ['a', 'b', 'x', 'y']);
+        // the left title covers `x`
+        assert_eq!(drawn_keys(20, &[&left, &right]), ['y', 'a', 'b']);
+        // the left title is cut off after `a(key)`
+        assert_eq!(drawn_keys(8, &[&left]), ['a']);
+        assert_eq!(drawn_keys(2, &[&left, &right]), [] as [char; 0]);
+    }
+}
@@ -0,0 +1,286 @@
+use crate::data::bar::Bar;
+use crate::indicators::moving_average::{ema, sma};
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, bail, eyre},
+};
+use serde::{Deserialize, Serialize};
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+/// Width of a band in standard deviations or ATRs, e.g. `2` or `1.5`.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Multiplier(pub(crate) f32);
+
+// by bits, so specs holding a multiplier can be cache keys; parsing never yields NaN
+impl PartialEq for Multiplier {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_bits() == other.0.to_bits()
+    }
+}
+impl Eq for Multiplier {}
+impl Hash for Multiplier {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
+}
+
+/// An envelope around a moving average of the closes.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub(crate) enum BandKind {
+    /// SMA ± `multiplier` standard deviations of the closes, both over `period`
+    Bollinger {
+        period: usize,
+        multiplier: Multiplier,
+    },
+    /// EMA ± `multiplier` average true ranges, both over `period`
+    Keltner {
+        period: usize,
+        multiplier: Multiplier,
+    },
+}
+
+/// Values of a band, one per bar; `None` until the period is filled.
+pub(crate) struct Band {
+    pub(crate) upper: Vec<Option<f32>>,
+    pub(crate) middle: Vec<Option<f32>>,
+    pub(crate) lower: Vec<Option<f32>>,
+}
+
+impl BandKind {
+    pub(crate) fn compute(&self, bars: &[Bar]) -> Band {
+        let closes = bars.iter().map(|bar| bar.close).collect::<Vec<_>>();
+        let (middle, widths) = match *self {
+            BandKind::Bollinger { period, multiplier } => (
+                sma(&closes, period),
+                scaled(std_dev(&closes, period), multiplier),
+            ),
+            BandKind::Keltner { period, multiplier } => {
+                (ema(&closes, period), scaled(atr(bars, period), multiplier))
+            }
+        };
+        let offset = |sign: f32| {
+            middle
+                .iter()
+                .zip(&widths)
+                .map(|(middle, width)| Some(middle.as_ref()? + sign * width.as_ref()?))
+                .collect()
+        };
+        Band {
+            upper: offset(1.0),
+            lower: offset(-1.0),
+            middle,
+        }
+    }
+
+    /// The bands drawn unless configured otherwise: with these multipliers, Bollinger bands
+    /// inside the Keltner channel mark a volatility squeeze.
+    pub(crate) fn defaults() -> Vec<Self> {
+        vec![
+            BandKind::Bollinger {
+                period: 20,
+                multiplier: Multiplier(2.0),
+            },
+            BandKind::Keltner {
+                period: 20,
+                multiplier: Multiplier(1.5),
+            },
+        ]
+    }
+}
+
+impl std::fmt::Display for BandKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BandKind::Bollinger { period, multiplier } => write!(f, "BB {period} {}", multiplier.0),
+            BandKind::Keltner { period, multiplier } => write!(f, "KC {period} {}", multiplier.0),
+        }
+    }
+}
+
+/// Parses `<kind>:<period>:<multiplier>` with kind `bb` (Bollinger) or `kc` (Keltner), e.g.
+/// `bb:20:2`.
+impl FromStr for BandKind {
+    type Err = color_eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let [kind, period, multiplier] = s.split(':').collect::<Vec<_>>()[..] else {
+            bail!("expected <kind>:<period>:<multiplier>, e.g. bb:20:2");
+        };
+        let period = period
+            .trim()
+            .parse()
+            .wrap_err_with(|| format!("invalid period '{period}'"))?;
+        if period == 0 {
+            bail!("the period must be at least 1");
+        }
+        let multiplier = multiplier
+            .trim()
+            .parse::<f32>()
+            .ok()
+            .filter(|m| m.is_finite() && *m > 0.0)
+            .map(Multiplier)
+            .ok_or_else(|| {
+                eyre!("invalid multiplier '{multiplier}', expected a positive number")
+            })?;
+        match kind.trim().to_ascii_lowercase().as_str() {
+            "bb" => Ok(BandKind::Bollinger { period, multiplier }),
+            "kc" => Ok(BandKind::Keltner { period, multiplier }),
+            _ => bail!("unknown band '{kind}', expected bb or kc"),
+        }
+    }
+}
+// as `bb:20:2` in the config file
+impl TryFrom<String> for BandKind {
+    type Error = color_eyre::Report;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+impl From<BandKind> for String {
+    fn from(band: BandKind) -> Self {
+        match band {
+            BandKind::Bollinger { period, multiplier } => format!("bb:{period}:{}", multiplier.0),
+            BandKind::Keltner { period, multiplier } => format!("kc:{period}:{}", multiplier.0),
+        }
+    }
+}
+
+fn scaled(values: Vec<Option<f32>>, multiplier: Multiplier) -> Vec<Option<f32>> {
+    values
+        .into_iter()
+        .map(|value| value.map(|value| value * multiplier.0))
+        .collect()
+}
+
+/// Population standard deviation over the last `period` values.
+pub(crate) fn std_dev(values: &[f32], period: usize) -> Vec<Option<f32>> {
+    let mut deviations = vec![None; values.len()];
+    if period == 0 || values.len() < period {
+        return deviations;
+    }
+    for (window, deviation) in values.windows(period).zip(&mut deviations[period - 1..]) {
+        let mean = window.iter().map(|v| *v as f64).sum::<f64>() / period as f64;
+        let variance = window
+            .iter()
+            .map(|v| (*v as f64 - mean).powi(2))
+            .sum::<f64>()
+            / period as f64;
+        *deviation = Some(variance.sqrt() as f32);
+    }
+    deviations
+}
+
+/// Average true range, with Wilder's smoothing over `period` bars.
+pub(crate) fn atr(bars: &[Bar], period: usize) -> Vec<Option<f32>> {
+    let true_ranges = bars
+        .iter()
+        .enumerate()
+        .map(|(ix, bar)| {
+            let range = bar.high - bar.low;
+            match ix.checked_sub(1) {
+                // gaps from the previous close count as range too
+                Some(prev) => range
+                    .max((bar.high - bars[prev].close).abs())
+                    .max((bar.low - bars[prev].close).abs()),
+                None => range,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut averages = sma(&true_ranges, period);
+    let Some(mut average) = period
+        .checked_sub(1)
+        .and_then(|ix| averages.get(ix).copied().flatten())
+    else {
+        return averages;
+    };
+    for ix in period..true_ranges.len() {
+        average += (true_ranges[ix] - average) / period as f32;
+        averages[ix] = Some(average);
+    }
+    averages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::bar::test_support::{assert_close, bars};
+
+    #[test]
+    fn bollinger() {
+        let closes = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        // half the difference of each pair
+        assert_close(
+            &std_dev(&closes, 2),
+            &[
+                None,
+                Some(1.0),
+                Some(0.0),
+                Some(0.0),
+                Some(0.5),
+                Some(0.0),
+                Some(1.0),
+                Some(1.0),
+            ],
+        );
+
+        let bars = bars(&closes.map(|close| (close, close, close, close)));
+        let band = BandKind::Bollinger {
+            period: 8,
+            multiplier: Multiplier(2.0),
+        }
+        .compute(&bars);
+        // mean 5, standard deviation 2
+        let mut expected = [None; 8];
+        expected[7] = Some(5.0);
+        assert_close(&band.middle, &expected);
+        expected[7] = Some(9.0);
+        assert_close(&band.upper, &expected);
+        expected[7] = Some(1.0);
+        assert_close(&band.lower, &expected);
+
+        // fewer bars than the period
+        let band = BandKind::Bollinger {
+            period: 8,
+            multiplier: Multiplier(2.0),
+        }
+        .compute(&bars[..7]);
+        for values in [band.upper, band.middle, band.lower] {
+            assert_close(&values, &[None; 7]);
+        }
+    }
+
+    #[test]
+    fn average_true_range() {
+        let bars = bars(&[
+            (9.0, 10.0, 8.0, 9.0),
+            (11.0, 12.0, 9.0, 11.0),
+            (10.5, 11.0, 10.0, 10.5),
+            // a gap up from the previous close
+            (14.0, 15.0, 13.0, 14.0),
+            (12.0, 14.0, 12.0, 12.0),
+        ]);
+        // true ranges 2, 3, 1, 4.5, 2; the first average is their SMA, then Wilder's smoothing
+        let second = 2.0 + (4.5 - 2.0) / 3.0;
+        assert_close(
+            &atr(&bars, 3),
+            &[
+                None,
+                None,
+                Some(2.0),
+                Some(second),
+                Some(second + (2.0 - second) / 3.0),
+            ],
+        );
+        assert_close(
+            &atr(&bars, 1),
+            &[Some(2.0), Some(3.0), Some(1.0), Some(4.5), Some(2.0)],
+        );
+        // fewer bars than the period
+        assert_close(&atr(&bars[..2], 3), &[None, None]);
+        assert_close(&atr(&[], 3), &[]);
+    }
+}
@@ -0,0 +1,210 @@
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, bail, eyre},
+};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use strum_macros::{Display, EnumString};
+
+/// How the values within the period are weighted.
+#[derive(Display, EnumString, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[strum(serialize_all = "UPPERCASE", ascii_case_insensitive)]
+pub(crate) enum MaKind {
+    /// simple: all values weigh the same
+    Sma,
+    /// exponential: weights decay by `2 / (period + 1)` per bar, seeded with the SMA
+    Ema,
+    /// weighted: linearly decreasing weights, the latest value weighs `period`
+    Wma,
+}
+
+/// A moving average of the closes, e.g. `SMA 20`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub(crate) struct MovingAverage {
+    pub(crate) kind: MaKind,
+    pub(crate) period: usize,
+}
+impl MovingAverage {
+    pub(crate) fn new(kind: MaKind, period: usize) -> Self {
+        Self { kind, period }
+    }
+
+    /// The moving average of `values`, one per value; `None` until `period` values are in.
+    pub(crate) fn compute(&self, values: &[f32]) -> Vec<Option<f32>> {
+        match self.kind {
+            MaKind::Sma => sma(values, self.period),
+            MaKind::Ema => ema(values, self.period),
+            MaKind::Wma => wma(values, self.period),
+        }
+    }
+
+    /// The averages drawn unless configured otherwise.
+    pub(crate) fn defaults() -> Vec<Self> {
+        vec![Self::new(MaKind::Sma, 20), Self::new(MaKind::Ema, 50)]
+    }
+}
+
+impl std::fmt::Display for MovingAverage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.kind, self.period)
+    }
+}
+
+/// Parses `<kind>:<period>`, e.g. `ema:50`.
+impl FromStr for MovingAverage {
+    type Err = color_eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (kind, period) = s
+            .split_once(':')
+            .ok_or_else(|| eyre!("expected <kind>:<period>, e.g. ema:50"))?;
+        let kind = kind
+            .trim()
+            .parse()
+            .map_err(|_| eyre!("unknown moving average '{kind}', expected sma, ema or wma"))?;
+        let period = period
+            .trim()
+            .parse()
+            .wrap_err_with(|| format!("invalid period '{period}'"))?;
+        if period == 0 {
+            bail!("the period must be at least 1");
+        }
+        Ok(Self::new(kind, period))
+    }
+}
+
+// as `ema:50` in the config file
+impl TryFrom<String> for MovingAverage {
+    type Error = color_eyre::Report;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+impl From<MovingAverage> for String {
+    fn from(average: MovingAverage) -> Self {
+        format!(
+            "{}:{}",
+            average.kind.to_string().to_lowercase(),
+            average.period
+        )
+    }
+}
+
+pub(crate) fn sma(values: &[f32], period: usize) -> Vec<Option<f32>> {
+    let mut averages = vec![None; values.len()];
+    if period == 0 || values.len() < period {
+        return averages;
+    }
+    // running sum in f64, so long series do not accumulate rounding errors
+    let mut sum = values[..period].iter().map(|v| *v as f64).sum::<f64>();
+    averages[period - 1] = Some((sum / period as f64) as f32);
+    for ix in period..values.len() {
+        sum += values[ix] as f64 - values[ix - period] as f64;
+        averages[ix] = Some((sum / period as f64) as f32);
+    }
+    averages
+}
+
+pub(crate) fn ema(values: &[f32], period: usize) -> Vec<Option<f32>> {
+    let mut averages = sma(values, period);
+    let Some(seed) = period
+        .checked_sub(1)
+        .and_then(|ix| averages.get(ix).copied().flatten())
+    else {
+        return averages;
+    };
+    let alpha = 2.0 / (period as f32 + 1.0);
+    let mut average = seed;
+    for ix in period..values.len() {
+        average += alpha * (values[ix] - average);
+        averages[ix] = Some(average);
+    }
+    averages
+}
+
+pub(crate) fn wma(values: &[f32], period: usize) -> Vec<Option<f32>> {
+    let mut averages = vec![None; values.len()];
+    if period == 0 || values.len() < period {
+        return averages;
+    }
+    let weights = (period * (period + 1) / 2) as f64;
+    for (window, average) in values.windows(period).zip(&mut averages[period - 1..]) {
+        let sum = window
+            .iter()
+            .enumerate()
+            .map(|(ix, v)| (ix + 1) as f64 * *v as f64)
+            .sum::<f64>();
+        *average = Some((sum / weights) as f32);
+    }
+    averages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::bar::test_support::assert_close;
+
+    const VALUES: [f32; 6] = [2.0, 4.0, 6.0, 8.0, 4.0, 1.0];
+
+    #[test]
+    fn simple() {
+        assert_close(
+            &sma(&VALUES, 3),
+            &[
+                None,
+                None,
+                Some(4.0),
+                Some(6.0),
+                Some(6.0),
+                Some(13.0 / 3.0),
+            ],
+        );
+        assert_close(&sma(&VALUES, 1), &VALUES.map(Some));
+    }
+
+    #[test]
+    fn exponential() {
+        // alpha 0.5, seeded with the first SMA
+        assert_close(
+            &ema(&VALUES, 3),
+            &[None, None, Some(4.0), Some(6.0), Some(5.0), Some(3.0)],
+        );
+    }
+
+    #[test]
+    fn weighted() {
+        // weights 1, 2, 3 of 6
+        assert_close(
+            &wma(&VALUES, 3),
+            &[
+                None,
+                None,
+                Some(28.0 / 6.0),
+                Some(40.0 / 6.0),
+                Some(34.0 / 6.0),
+                Some(19.0 / 6.0),
+            ],
+        );
+    }
+
+    #[test]
+    fn warm_up() {
+        for (kind, last) in [
+            (MaKind::Sma, 25.0 / 6.0),
+            (MaKind::Ema, 25.0 / 6.0),
+            (MaKind::Wma, 86.0 / 21.0),
+        ] {
+            // as many values as the period, one average
+            let average = MovingAverage::new(kind, 6);
+            let mut expected = [None; 6];
+            expected[5] = Some(last);
+            assert_close(&average.compute(&VALUES), &expected);
+            // fewer values than the period
+            assert_close(&average.compute(&VALUES[..5]), &[None; 5]);
+            assert_close(&average.compute(&[]), &[]);
+            assert_close(&MovingAverage::new(kind, 0).compute(&VALUES), &[None; 6]);
+        }
+    }
+}
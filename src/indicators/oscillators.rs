@@ -0,0 +1,214 @@
+use crate::data::bar::Bar;
+use crate::indicators::moving_average::{ema, sma};
+use strum_macros::Display;
+
+const RSI_PERIOD: usize = 14;
+const MACD_FAST: usize = 12;
+const MACD_SLOW: usize = 26;
+const MACD_SIGNAL: usize = 9;
+const STOCHASTIC_K: usize = 14;
+const STOCHASTIC_D: usize = 3;
+
+/// Indicators drawn in their own pane below the price chart, in this order.
+#[derive(Display, Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(crate) enum Oscillator {
+    #[strum(to_string = "RSI 14")]
+    Rsi,
+    #[strum(to_string = "MACD 12 26 9")]
+    Macd,
+    #[strum(to_string = "Stochastic 14 3")]
+    Stochastic,
+}
+
+/// Values of a MACD, one per bar; `None` until the slow (or signal) period is filled.
+pub(crate) struct Macd {
+    /// fast EMA minus slow EMA of the closes
+    pub(crate) line: Vec<Option<f32>>,
+    /// EMA of the line
+    pub(crate) signal: Vec<Option<f32>>,
+    /// line minus signal
+    pub(crate) histogram: Vec<Option<f32>>,
+}
+
+/// Values of a stochastic oscillator in percent, one per bar.
+pub(crate) struct Stochastic {
+    /// where the close sits within the range of the last bars
+    pub(crate) k: Vec<Option<f32>>,
+    /// SMA of %K
+    pub(crate) d: Vec<Option<f32>>,
+}
+
+/// Relative strength index of the closes, with Wilder's smoothing.
+pub(crate) fn rsi(closes: &[f32]) -> Vec<Option<f32>> {
+    let period = RSI_PERIOD;
+    let mut values = vec![None; closes.len()];
+    if closes.len() <= period {
+        return values;
+    }
+    let changes = closes.windows(2).map(|pair| pair[1] - pair[0]);
+    let (gains, losses): (Vec<f32>, Vec<f32>) = changes
+        .map(|change| (change.max(0.0), (-change).max(0.0)))
+        .unzip();
+
+    let mut gain = gains[..period].iter().sum::<f32>() / period as f32;
+    let mut loss = losses[..period].iter().sum::<f32>() / period as f32;
+    let index = |gain: f32, loss: f32| match loss {
+        0.0 => 100.0,
+        loss => 100.0 - 100.0 / (1.0 + gain / loss),
+    };
+    values[period] = Some(index(gain, loss));
+    // change `ix` leads to close `ix + 1`
+    for ix in period..gains.len() {
+        gain += (gains[ix] - gain) / period as f32;
+        loss += (losses[ix] - loss) / period as f32;
+        values[ix + 1] = Some(index(gain, loss));
+    }
+    values
+}
+
+pub(crate) fn macd(closes: &[f32]) -> Macd {
+    let fast = ema(closes, MACD_FAST);
+    let slow = ema(closes, MACD_SLOW);
+    let line = difference(&fast, &slow);
+    let signal = on_defined(&line, |line| ema(line, MACD_SIGNAL));
+    let histogram = difference(&line, &signal);
+    Macd {
+        line,
+        signal,
+        histogram,
+    }
+}
+
+pub(crate) fn stochastic(bars: &[Bar]) -> Stochastic {
+    let mut k = vec![None; bars.len()];
+    if bars.len() >= STOCHASTIC_K {
+        for (window, k) in bars.windows(STOCHASTIC_K).zip(&mut k[STOCHASTIC_K - 1..]) {
+            let low = window
+                .iter()
+                .map(|bar| bar.low)
+                .fold(f32::INFINITY, f32::min);
+            let high = window
+                .iter()
+                .map(|bar| bar.high)
+                .fold(f32::NEG_INFINITY, f32::max);
+            let close = window[window.len() - 1].close;
+            // a flat range has no position within it, call it the middle
+            *k = Some(match high - low {
+                0.0 => 50.0,
+                range => 100.0 * (close - low) / range,
+            });
+        }
+    }
+    let d = on_defined(&k, |k| sma(k, STOCHASTIC_D));
+    Stochastic { k, d }
+}
+
+fn difference(a: &[Option<f32>], b: &[Option<f32>]) -> Vec<Option<f32>> {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| Some(a.as_ref()? - b.as_ref()?))
+        .collect()
+}
+
+/// Applies `indicator` to the values after the leading `None`s, e.g. a signal line to a MACD
+/// that only starts once its slow period is filled.
+fn on_defined(
+    values: &[Option<f32>],
+    indicator: impl Fn(&[f32]) -> Vec<Option<f32>>,
+) -> Vec<Option<f32>> {
+    let start = values
+        .iter()
+        .position(Option::is_some)
+        .unwrap_or(values.len());
+    let defined = values[start..]
+        .iter()
+        .map(|value| value.unwrap_or_default());
+    let mut result = vec![None; start];
+    result.extend(indicator(&defined.collect::<Vec<_>>()));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::bar::test_support::{assert_close, bars};
+
+    /// `None` for the first `warm_up` values, then `values`.
+    fn after(warm_up: usize, values: &[f32]) -> Vec<Option<f32>> {
+        let mut expected = vec![None; warm_up];
+        expected.extend(values.iter().copied().map(Some));
+        expected
+    }
+
+    #[test]
+    fn relative_strength() {
+        // 7 gains of 2 and 7 losses of 1, then a gain of 3
+        let mut closes = vec![100.0];
+        for ix in 0..14 {
+            let change = if ix % 2 == 0 { 2.0 } else { -1.0 };
+            closes.push(closes[ix] + change);
+        }
+        closes.push(closes[14] + 3.0);
+        // average gain 1 and loss 0.5, then 16/14 and 6.5/14 by Wilder's smoothing
+        assert_close(
+            &rsi(&closes),
+            &after(
+                14,
+                &[100.0 - 100.0 / 3.0, 100.0 - 100.0 / (1.0 + 16.0 / 6.5)],
+            ),
+        );
+        // 13 changes, one short of the period
+        assert_close(&rsi(&closes[..14]), &[None; 14]);
+    }
+
+    #[test]
+    fn relative_strength_without_losses() {
+        let rising = (0..16).map(|ix| ix as f32).collect::<Vec<_>>();
+        assert_close(&rsi(&rising), &after(14, &[100.0, 100.0]));
+        assert_close(&rsi(&[5.0; 16]), &after(14, &[100.0, 100.0]));
+    }
+
+    #[test]
+    fn moving_average_convergence_divergence() {
+        // on a rising line, each EMA lags by half its period less one: 5.5 and 12.5
+        let closes = (0..40).map(|ix| ix as f32).collect::<Vec<_>>();
+        let macd = macd(&closes);
+        assert_close(&macd.line, &after(25, &[7.0; 15]));
+        assert_close(&macd.signal, &after(33, &[7.0; 7]));
+        assert_close(&macd.histogram, &after(33, &[0.0; 7]));
+
+        let macd = super::macd(&closes[..25]);
+        for values in [macd.line, macd.signal, macd.histogram] {
+            assert_close(&values, &[None; 25]);
+        }
+    }
+
+    #[test]
+    fn stochastic_oscillator() {
+        // closes 5 above the lows and 5 below the highs, up to the last one at its high
+        let mut bars = bars(
+            &(0..16)
+                .map(|ix| ix as f32)
+                .map(|low| (low + 5.0, low + 10.0, low, low + 5.0))
+                .collect::<Vec<_>>(),
+        );
+        bars[15].close = 25.0;
+        let stochastic = stochastic(&bars);
+        // the close 18 above the lowest low, of a range of 23
+        let k = 100.0 * 18.0 / 23.0;
+        assert_close(&stochastic.k, &after(13, &[k, k, 100.0]));
+        assert_close(&stochastic.d, &after(15, &[(2.0 * k + 100.0) / 3.0]));
+
+        let stochastic = super::stochastic(&bars[..13]);
+        assert_close(&stochastic.k, &[None; 13]);
+        assert_close(&stochastic.d, &[None; 13]);
+    }
+
+    #[test]
+    fn stochastic_without_range() {
+        let bars = bars(&[(5.0, 5.0, 5.0, 5.0); 16]);
+        let stochastic = stochastic(&bars);
+        assert_close(&stochastic.k, &after(13, &[50.0; 3]));
+        assert_close(&stochastic.d, &after(15, &[50.0]));
+    }
+}
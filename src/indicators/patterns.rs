@@ -0,0 +1,276 @@
+use crate::data::bar::Bar;
+use strum_macros::Display;
+
+/// Bars looked back to tell whether a reversal pattern follows a trend.
+const TREND_BARS: usize = 5;
+/// A candle with a body up to this share of its range is a doji.
+const DOJI_BODY: f32 = 0.1;
+
+/// Which way a pattern hints the price will go.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Bias {
+    Bullish,
+    Bearish,
+    Neutral,
+}
+
+#[derive(Display, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Pattern {
+    Doji,
+    Hammer,
+    #[strum(to_string = "Shooting star")]
+    ShootingStar,
+    #[strum(to_string = "Bullish engulfing")]
+    BullishEngulfing,
+    #[strum(to_string = "Bearish engulfing")]
+    BearishEngulfing,
+    #[strum(to_string = "Bullish harami")]
+    BullishHarami,
+    #[strum(to_string = "Bearish harami")]
+    BearishHarami,
+    #[strum(to_string = "Morning star")]
+    MorningStar,
+    #[strum(to_string = "Evening star")]
+    EveningStar,
+    #[strum(to_string = "Three white soldiers")]
+    ThreeWhiteSoldiers,
+    #[strum(to_string = "Three black crows")]
+    ThreeBlackCrows,
+}
+impl Pattern {
+    pub(crate) fn bias(&self) -> Bias {
+        match self {
+            Pattern::Doji => Bias::Neutral,
+            Pattern::Hammer
+            | Pattern::BullishEngulfing
+            | Pattern::BullishHarami
+            | Pattern::MorningStar
+            | Pattern::ThreeWhiteSoldiers => Bias::Bullish,
+            Pattern::ShootingStar
+            | Pattern::BearishEngulfing
+            | Pattern::BearishHarami
+            | Pattern::EveningStar
+            | Pattern::ThreeBlackCrows => Bias::Bearish,
+        }
+    }
+
+    /// Short label, for the chart annotations.
+    pub(crate) fn abbreviation(&self) -> &'static str {
+        match self {
+            Pattern::Doji => "Doji",
+            Pattern::Hammer => "Ham",
+            Pattern::ShootingStar => "SStar",
+            Pattern::BullishEngulfing | Pattern::BearishEngulfing => "Engulf",
+            Pattern::BullishHarami | Pattern::BearishHarami => "Harami",
+            Pattern::MorningStar => "MStar",
+            Pattern::EveningStar => "EStar",
+            Pattern::ThreeWhiteSoldiers => "3WS",
+            Pattern::ThreeBlackCrows => "3BC",
+        }
+    }
+}
+
+/// A pattern completed by the bar at `ix`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct PatternMatch {
+    pub(crate) ix: usize,
+    pub(crate) pattern: Pattern,
+}
+
+/// All patterns in `bars`, by the bar completing them, oldest first.
+pub(crate) fn scan(bars: &[Bar]) -> Vec<PatternMatch> {
+    let mut matches = vec![];
+    for ix in 0..bars.len() {
+        let candle = Candle(&bars[ix]);
+        let trend = trend(bars, ix);
+        let mut found = |pattern| matches.push(PatternMatch { ix, pattern });
+
+        if candle.is_doji() {
+            found(Pattern::Doji);
+        } else if candle.lower_wick() >= 2.0 * candle.body()
+            && candle.upper_wick() <= 0.5 * candle.body()
+        {
+            // a hammer after a rise is a hanging man, which we do not look for
+            if trend == Some(Bias::Bearish) {
+                found(Pattern::Hammer);
+            }
+        } else if candle.upper_wick() >= 2.0 * candle.body()
+            && candle.lower_wick() <= 0.5 * candle.body()
+            && trend == Some(Bias::Bullish)
+        {
+            found(Pattern::ShootingStar);
+        }
+
+        if let Some(previous) = ix.checked_sub(1).map(|prev| Candle(&bars[prev])) {
+            let engulfs = candle.body_top() >= previous.body_top()
+                && candle.body_bottom() <= previous.body_bottom()
+                && candle.body() > previous.body();
+            let within = candle.body_top() <= previous.body_top()
+                && candle.body_bottom() >= previous.body_bottom()
+                && candle.body() < previous.body();
+            match (previous.bias(), candle.bias()) {
+                (Bias::Bearish, Bias::Bullish) if engulfs => found(Pattern::BullishEngulfing),
+                (Bias::Bullish, Bias::Bearish) if engulfs => found(Pattern::BearishEngulfing),
+                (Bias::Bearish, Bias::Bullish) if within => found(Pattern::BullishHarami),
+                (Bias::Bullish, Bias::Bearish) if within => found(Pattern::BearishHarami),
+                _ => {}
+            }
+        }
+
+        if ix >= 2 {
+            let [first, middle, last] = [ix - 2, ix - 1, ix].map(|ix| Candle(&bars[ix]));
+            let small_middle = middle.body() <= 0.3 * first.body();
+            let midpoint = (first.body_top() + first.body_bottom()) / 2.0;
+            match (first.bias(), last.bias()) {
+                (Bias::Bearish, Bias::Bullish) if small_middle && last.0.close > midpoint => {
+                    found(Pattern::MorningStar)
+                }
+                (Bias::Bullish, Bias::Bearish) if small_middle && last.0.close < midpoint => {
+                    found(Pattern::EveningStar)
+                }
+                _ => {}
+            }
+
+            let three = [first, middle, last];
+            let advancing = three
+                .windows(2)
+                .all(|pair| pair[1].0.close > pair[0].0.close && pair[1].opens_within(&pair[0]));
+            let declining = three
+                .windows(2)
+                .all(|pair| pair[1].0.close < pair[0].0.close && pair[1].opens_within(&pair[0]));
+            let strong = three
+                .iter()
+                .all(|candle| !candle.is_doji() && candle.body() >= 0.5 * candle.range());
+            if strong && advancing && three.iter().all(|c| c.bias() == Bias::Bullish) {
+                found(Pattern::ThreeWhiteSoldiers);
+            }
+            if strong && declining && three.iter().all(|c| c.bias() == Bias::Bearish) {
+                found(Pattern::ThreeBlackCrows);
+            }
+        }
+    }
+    matches
+}
+
+/// Direction of the closes over the bars before `ix`, `None` without enough history.
+fn trend(bars: &[Bar], ix: usize) -> Option<Bias> {
+    let start = &bars[ix.checked_sub(TREND_BARS)?];
+    let end = &bars[ix.checked_sub(1)?];
+    Some(if end.close > start.close {
+        Bias::Bullish
+    } else if end.close < start.close {
+        Bias::Bearish
+    } else {
+        Bias::Neutral
+    })
+}
+
+/// Shape measures of a bar.
+#[derive(Clone, Copy)]
+struct Candle<'a>(&'a Bar);
+impl Candle<'_> {
+    fn body(&self) -> f32 {
+        (self.0.close - self.0.open).abs()
+    }
+
+    fn range(&self) -> f32 {
+        self.0.high - self.0.low
+    }
+
+    fn body_top(&self) -> f32 {
+        self.0.open.max(self.0.close)
+    }
+
+    fn body_bottom(&self) -> f32 {
+        self.0.open.min(self.0.close)
+    }
+
+    fn upper_wick(&self) -> f32 {
+        self.0.high - self.body_top()
+    }
+
+    fn lower_wick(&self) -> f32 {
+        self.body_bottom() - self.0.low
+    }
+
+    fn is_doji(&self) -> bool {
+        self.range() > 0.0 && self.body() <= DOJI_BODY * self.range()
+    }
+
+    fn bias(&self) -> Bias {
+        if self.0.close > self.0.open {
+            Bias::Bullish
+        } else if self.0.close < self.0.open {
+            Bias::Bearish
+        } else {
+            Bias::Neutral
+        }
+    }
+
+    /// Whether this candle opens within the body of `previous`.
+    fn opens_within(&self, previous: &Candle) -> bool {
+        (previous.body_bottom()..=previous.body_top()).contains(&self.0.open)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::bar::test_support::bars;
+
+    #[test]
+    fn patterns_of_a_fixture() {
+        let bars = bars(&[
+            // a decline of weak candles, gapping down
+            (20.0, 21.0, 19.0, 19.5),
+            (19.0, 20.0, 18.0, 18.5),
+            (18.0, 19.0, 17.0, 17.5),
+            (17.0, 18.0, 16.0, 16.5),
+            (16.0, 17.0, 15.0, 15.5),
+            // 5: a long lower wick after the decline
+            (15.5, 15.75, 13.5, 15.0),
+            // 6: engulfing the body of the hammer
+            (14.75, 16.25, 14.5, 16.0),
+            // 7: no body
+            (16.0, 16.5, 15.5, 16.0),
+            // 8: up, doji, and down below the middle of the first
+            (16.0, 16.25, 13.75, 14.0),
+            (13.75, 14.0, 13.25, 13.5),
+            // 10: down, small, and up above the middle of the first
+            (13.75, 16.0, 13.5, 15.75),
+            // 12: three long bullish candles, each opening within the previous body
+            (15.5, 17.75, 15.25, 17.5),
+            (17.25, 19.5, 17.0, 19.25),
+            // 13: a long upper wick after the rise
+            (19.5, 20.5, 19.25, 19.25),
+            // 14: engulfing the body of the shooting star
+            (19.0, 21.25, 18.75, 21.0),
+            // 15: within the body of the previous candle
+            (20.5, 20.75, 19.25, 19.5),
+        ]);
+        assert_eq!(
+            scan(&bars)
+                .into_iter()
+                .map(|found| (found.ix, found.pattern))
+                .collect::<Vec<_>>(),
+            [
+                (5, Pattern::Hammer),
+                (6, Pattern::BullishEngulfing),
+                (7, Pattern::Doji),
+                (8, Pattern::EveningStar),
+                (10, Pattern::MorningStar),
+                (12, Pattern::ThreeWhiteSoldiers),
+                (13, Pattern::ShootingStar),
+                (14, Pattern::BullishEngulfing),
+                (15, Pattern::BearishHarami),
+            ]
+        );
+    }
+
+    #[test]
+    fn reversals_need_a_trend() {
+        // the hammer and shooting star shapes from above, without the bars before them
+        let bars = bars(&[(15.5, 15.75, 13.5, 15.0), (19.5, 20.5, 19.25, 19.25)]);
+        assert_eq!(scan(&bars), []);
+    }
+}
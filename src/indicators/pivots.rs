@@ -0,0 +1,210 @@
+use crate::data::bar::Bar;
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, bail, eyre},
+};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::str::FromStr;
+
+/// How many bars left and right of a swing high (low) must have lower highs (higher lows).
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub(crate) struct SwingStrength {
+    pub(crate) left: usize,
+    pub(crate) right: usize,
+}
+impl Default for SwingStrength {
+    fn default() -> Self {
+        Self { left: 3, right: 3 }
+    }
+}
+
+/// Parses `<left>:<right>`, e.g. `5:2`.
+impl FromStr for SwingStrength {
+    type Err = color_eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (left, right) = s
+            .split_once(':')
+            .ok_or_else(|| eyre!("expected <left>:<right>, e.g. 3:3"))?;
+        let bars = |value: &str| {
+            value
+                .trim()
+                .parse::<usize>()
+                .wrap_err_with(|| format!("invalid number of bars '{value}'"))
+        };
+        let (left, right) = (bars(left)?, bars(right)?);
+        if left == 0 || right == 0 {
+            bail!("a swing needs at least one bar on either side");
+        }
+        Ok(Self { left, right })
+    }
+}
+// as `3:3` in the config file
+impl TryFrom<String> for SwingStrength {
+    type Error = color_eyre::Report;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+impl From<SwingStrength> for String {
+    fn from(strength: SwingStrength) -> Self {
+        format!("{}:{}", strength.left, strength.right)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum SwingKind {
+    High,
+    Low,
+}
+
+/// A local extreme of the bars.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Swing {
+    pub(crate) ix: usize,
+    pub(crate) kind: SwingKind,
+    /// the high of a swing high, the low of a swing low
+    pub(crate) price: f32,
+}
+
+/// Swing highs and lows, oldest first.
+///
+/// A bar is a swing high if its high exceeds the highs of the `left` bars before it and is not
+/// exceeded by the `right` bars after it; swing lows likewise. The last `right` bars can not be
+/// confirmed yet and never are swings.
+pub(crate) fn swings(bars: &[Bar], strength: SwingStrength) -> Vec<Swing> {
+    let SwingStrength { left, right } = strength;
+    let mut swings = vec![];
+    for ix in left..bars.len().saturating_sub(right) {
+        let bar = &bars[ix];
+        let before = &bars[ix - left..ix];
+        let after = &bars[ix + 1..=ix + right];
+        if before.iter().all(|other| other.high < bar.high)
+            && after.iter().all(|other| other.high <= bar.high)
+        {
+            swings.push(Swing {
+                ix,
+                kind: SwingKind::High,
+                price: bar.high,
+            });
+        }
+        if before.iter().all(|other| other.low > bar.low)
+            && after.iter().all(|other| other.low >= bar.low)
+        {
+            swings.push(Swing {
+                ix,
+                kind: SwingKind::Low,
+                price: bar.low,
+            });
+        }
+    }
+    swings
+}
+
+/// Classic floor trader pivots of a session, derived from the previous session.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct FloorPivots {
+    /// bars of the session the levels apply to
+    pub(crate) bars: Range<usize>,
+    pub(crate) pivot: f32,
+    /// R1, R2, R3
+    pub(crate) resistances: [f32; 3],
+    /// S1, S2, S3
+    pub(crate) supports: [f32; 3],
+}
+
+/// Floor pivots for every session but the first, `sessions` being consecutive index ranges of
+/// `bars`.
+pub(crate) fn floor_pivots(bars: &[Bar], sessions: &[Range<usize>]) -> Vec<FloorPivots> {
+    sessions
+        .windows(2)
+        .map(|pair| {
+            let previous = &bars[pair[0].clone()];
+            let high = previous.iter().map(|bar| bar.high).fold(f32::MIN, f32::max);
+            let low = previous.iter().map(|bar| bar.low).fold(f32::MAX, f32::min);
+            let close = previous.last().map(|bar| bar.close).unwrap_or_default();
+
+            let pivot = (high + low + close) / 3.0;
+            let range = high - low;
+            FloorPivots {
+                bars: pair[1].clone(),
+                pivot,
+                resistances: [2.0 * pivot - low, pivot + range, high + 2.0 * (pivot - low)],
+                supports: [
+                    2.0 * pivot - high,
+                    pivot - range,
+                    low - 2.0 * (high - pivot),
+                ],
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::bar::test_support::bars;
+
+    #[test]
+    fn swing_points() {
+        // lows one below the highs
+        let bars =
+            bars(&[1.0, 3.0, 2.0, 2.0, 4.0, 4.0, 1.0].map(|high| (high, high, high - 1.0, high)));
+        let swing = |ix, kind, price| Swing { ix, kind, price };
+        // of equal highs (lows) the first one is the swing
+        assert_eq!(
+            swings(&bars, SwingStrength { left: 1, right: 1 }),
+            [
+                swing(1, SwingKind::High, 3.0),
+                swing(2, SwingKind::Low, 1.0),
+                swing(4, SwingKind::High, 4.0),
+            ]
+        );
+        // the low at 2 is no lower than the one at 0, the high at 1 lacks bars before it
+        assert_eq!(
+            swings(&bars, SwingStrength { left: 2, right: 1 }),
+            [swing(4, SwingKind::High, 4.0)]
+        );
+        // the last bars are not confirmed
+        assert_eq!(
+            swings(&bars[..5], SwingStrength { left: 1, right: 1 }).len(),
+            2
+        );
+        assert_eq!(swings(&bars[..1], SwingStrength::default()), []);
+    }
+
+    #[test]
+    fn floor_pivots_of_sessions() {
+        let bars = bars(&[
+            (8.0, 12.0, 7.0, 8.0),
+            (9.0, 11.0, 6.0, 9.0),
+            (8.5, 10.0, 8.0, 8.5),
+            (9.0, 9.5, 8.5, 9.0),
+            (9.0, 9.0, 9.0, 9.0),
+        ]);
+        assert_eq!(
+            floor_pivots(&bars, &[0..2, 2..4, 4..5]),
+            [
+                // high 12, low 6, close 9
+                FloorPivots {
+                    bars: 2..4,
+                    pivot: 9.0,
+                    resistances: [12.0, 15.0, 18.0],
+                    supports: [6.0, 3.0, 0.0],
+                },
+                // high 10, low 8, close 9
+                FloorPivots {
+                    bars: 4..5,
+                    pivot: 9.0,
+                    resistances: [10.0, 11.0, 12.0],
+                    supports: [8.0, 7.0, 6.0],
+                },
+            ]
+        );
+        // one session, none before it
+        assert_eq!(floor_pivots(&bars, std::slice::from_ref(&(0..5))), []);
+    }
+}
@@ -0,0 +1,147 @@
+use crate::data::bar::Bar;
+use crate::indicators::bands::atr;
+use crate::indicators::pivots::{Swing, SwingStrength, swings};
+use chrono::{DateTime, Utc};
+use std::ops::Range;
+
+/// Period of the ATR the clustering tolerance derives from.
+const ATR_PERIOD: usize = 14;
+/// Swings closer than this many ATRs to a zone join it.
+const TOLERANCE_ATRS: f32 = 0.5;
+/// A zone needs at least this many swings touching it.
+const MIN_TOUCHES: usize = 2;
+/// At most this many zones, the most touched ones.
+pub(crate) const MAX_ZONES: usize = 6;
+
+/// A price range where swings turned repeatedly.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Zone {
+    pub(crate) range: Range<f32>,
+    /// swing highs and lows within the range
+    pub(crate) touches: usize,
+    /// opening time of the latest bar touching the zone
+    pub(crate) last_touch: DateTime<Utc>,
+}
+
+/// Support and resistance zones, highest first.
+///
+/// Swing highs and lows (see [`swings`]) are sorted by price and clustered while the next one is
+/// within half an average true range of the cluster; clusters with enough touches become zones,
+/// at least half an ATR tall so they stay visible.
+pub(crate) fn zones(bars: &[Bar], strength: SwingStrength) -> Vec<Zone> {
+    let ranges = atr(bars, ATR_PERIOD)
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    let Some(average_range) =
+        (!ranges.is_empty()).then(|| ranges.iter().sum::<f32>() / ranges.len() as f32)
+    else {
+        return vec![];
+    };
+    let tolerance = TOLERANCE_ATRS * average_range;
+
+    let mut swings = swings(bars, strength);
+    swings.sort_by(|a, b| a.price.total_cmp(&b.price));
+
+    let mut clusters: Vec<Vec<Swing>> = vec![];
+    for swing in swings {
+        match clusters.last_mut() {
+            // sorted, so the last swing of the cluster is its highest
+            Some(cluster) if swing.price - cluster[cluster.len() - 1].price <= tolerance => {
+                cluster.push(swing)
+            }
+            _ => clusters.push(vec![swing]),
+        }
+    }
+
+    let mut zones = clusters
+        .into_iter()
+        .filter(|cluster| cluster.len() >= MIN_TOUCHES)
+        .map(|cluster| {
+            let low = cluster[0].price;
+            let high = cluster[cluster.len() - 1].price;
+            let padding = ((tolerance - (high - low)) / 2.0).max(0.0);
+            let last_ix = cluster
+                .iter()
+                .map(|swing| swing.ix)
+                .max()
+                .unwrap_or_default();
+            Zone {
+                range: low - padding..high + padding,
+                touches: cluster.len(),
+                last_touch: bars[last_ix].time,
+            }
+        })
+        .collect::<Vec<_>>();
+    // most touched first, ties by the more recent touch
+    zones.sort_by_key(|zone| std::cmp::Reverse((zone.touches, zone.last_touch)));
+    zones.truncate(MAX_ZONES);
+    zones.sort_by(|a, b| b.range.start.total_cmp(&a.range.start));
+    zones
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::bar::test_support;
+
+    /// Hourly bars two high around the closes, each a step of one from the previous: every true
+    /// range is 2, and so is the ATR.
+    fn bars(closes: impl Iterator<Item = f32>) -> Vec<Bar> {
+        let prices = closes.map(|close| (close, close + 1.0, close - 1.0, close));
+        test_support::bars(&prices.collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn merges_swings_within_half_an_atr() {
+        let closes = (10..=20)
+            .chain((14..=19).rev())
+            .chain(15..=21)
+            .chain((17..=20).rev())
+            .chain(18..=26)
+            .chain((23..=25).rev())
+            .chain(24..=26)
+            .map(|close| close as f32);
+        let bars = bars(closes);
+        // swing highs 21, 22 and 27, swing lows 13, 16 and 22: those at 21 and 22 are within half
+        // the ATR of 2 of each other, the others too far from any to make a zone
+        assert_eq!(
+            zones(&bars, SwingStrength::default()),
+            [Zone {
+                range: 21.0..22.0,
+                touches: 3,
+                last_touch: bars[39].time,
+            }]
+        );
+    }
+
+    #[test]
+    fn pads_narrow_zones() {
+        // swing highs at 21, lows at 13
+        let closes = (10..=20)
+            .chain((14..=19).rev())
+            .chain(15..=20)
+            .chain((14..=19).rev())
+            .chain(15..=17)
+            .map(|close| close as f32);
+        let bars = bars(closes);
+        // half an ATR tall
+        assert_eq!(
+            zones(&bars, SwingStrength::default()),
+            [
+                Zone {
+                    range: 20.5..21.5,
+                    touches: 2,
+                    last_touch: bars[22].time,
+                },
+                Zone {
+                    range: 12.5..13.5,
+                    touches: 2,
+                    last_touch: bars[28].time,
+                },
+            ]
+        );
+        // no ATR yet
+        assert_eq!(zones(&bars[..13], SwingStrength::default()), []);
+    }
+}
@@ -0,0 +1,272 @@
+use crate::{HOTKEY_STYLE, hotkeys::HotkeyLine};
+use color_eyre::{
+    Result,
+    eyre::{Ok, bail, eyre},
+};
+use crossterm::event::KeyCode;
+use ratatui::text::Span;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+};
+
+/// The commands of a view, displayed as their names in the config file.
+pub(crate) trait Command: Copy + Ord + Display + 'static {
+    /// The keys of every command, unless the config file binds others.
+    const DEFAULTS: &'static [(Self, &'static [KeyCode])];
+}
+
+/// The commands of a view and the keys bound to them, a `[keys.<view>]` table in the config file.
+/// A key is bound to one command at most.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(
+    try_from = "BTreeMap<C, Keys>",
+    into = "BTreeMap<C, Keys>",
+    bound(
+        serialize = "C: Command + Serialize",
+        deserialize = "C: Command + Deserialize<'de>"
+    )
+)]
+pub(crate) struct Keymap<C: Ord>(BTreeMap<C, Keys>);
+impl<C: Command> TryFrom<BTreeMap<C, Keys>> for Keymap<C> {
+    type Error = color_eyre::Report;
+
+    fn try_from(commands: BTreeMap<C, Keys>) -> Result<Self> {
+        let mut bound = HashMap::new();
+        for (command, keys) in &commands {
+            for key in &keys.0 {
+                match bound.insert(*key, *command) {
+                    Some(other) if other != *command => bail!(
+                        "Key '{}' is bound to both {other} and {command}",
+                        config_name(*key)
+                    ),
+                    _ => {}
+                }
+            }
+        }
+        Ok(Self(commands))
+    }
+}
+impl<C: Ord> From<Keymap<C>> for BTreeMap<C, Keys> {
+    fn from(keymap: Keymap<C>) -> Self {
+        keymap.0
+    }
+}
+impl<C: Ord> Default for Keymap<C> {
+    /// Nothing bound, e.g. a view missing from the config file.
+    fn default() -> Self {
+        Self(BTreeMap::new())
+    }
+}
+impl<C: Command> Keymap<C> {
+    /// Every command bound to its [default keys](Command::DEFAULTS).
+    pub(crate) fn defaults() -> Self {
+        Self(
+            C::DEFAULTS
+                .iter()
+                .map(|(command, keys)| (*command, Keys(keys.to_vec())))
+                .collect(),
+        )
+    }
+
+    /// `self`, with the commands it lacks bound as in `fallback`. A key bound here is taken from
+    /// the command it was bound to there, so rebinding a key needs no unbinding.
+    pub(crate) fn or(self, fallback: Self) -> Self {
+        let mut commands = fallback.0;
+        for keys in commands.values_mut() {
+            keys.0.retain(|key| self.command(*key).is_none());
+        }
+        commands.extend(self.0);
+        Self(commands)
+    }
+
+    /// The command `key` is bound to, if any.
+    pub(crate) fn command(&self, key: KeyCode) -> Option<C> {
+        self.0
+            .iter()
+            .find(|(_, keys)| keys.0.contains(&key))
+            .map(|(command, _)| *command)
+    }
+
+    /// A title listing the keys of the commands of every hint and what they do, e.g.
+    /// `j(↓)/k(↑)(select)`, or `t` `imeframe` where the label starts with the only key. Hints of
+    /// unbound commands are left out, and so are those beyond `width`.
+    pub(crate) fn hints(&self, hints: &[(&[C], &str)], width: u16) -> HotkeyLine {
+        let mut line = HotkeyLine::default();
+        for (commands, label) in hints {
+            let keys = commands
+                .iter()
+                .filter_map(|command| self.0.get(command))
+                .filter(|keys| !keys.0.is_empty())
+                .collect::<Vec<_>>();
+            if keys.is_empty() {
+                continue;
+            }
+            let mut hint = HotkeyLine::default();
+            if line.width() > 0 {
+                hint.push("──");
+            }
+            // the key as the initial of the label, e.g. `t` `imeframe`
+            let initial = match keys.as_slice() {
+                [Keys(codes)] => match codes.as_slice() {
+                    [KeyCode::Char(c)] if *c != ' ' && label.starts_with(*c) => Some(*c),
+                    _ => None,
+                },
+                _ => None,
+            };
+            match initial {
+                Some(c) => {
+                    hint.push_key(c.to_string(), KeyCode::Char(c));
+                    hint.push(label[c.len_utf8()..].to_string());
+                }
+                None => {
+                    for (ix, keys) in keys.iter().enumerate() {
+                        if ix > 0 {
+                            hint.push(Span::styled("/", HOTKEY_STYLE));
+                        }
+                        push_keys(&mut hint, keys);
+                    }
+                    hint.push(format!("({label})"));
+                }
+            }
+            if line.width() + hint.width() > width {
+                break;
+            }
+            line.append(hint);
+        }
+        line
+    }
+}
+
+/// The first of `keys`, the others in parentheses, e.g. `j(↓)`, each a hotkey of its own.
+fn push_keys(line: &mut HotkeyLine, keys: &Keys) {
+    for (ix, key) in keys.0.iter().enumerate() {
+        match ix {
+            0 => {}
+            1 => line.push(Span::styled("(", HOTKEY_STYLE)),
+            _ => line.push(Span::styled(",", HOTKEY_STYLE)),
+        }
+        line.push_key(key_name(*key), *key);
+    }
+    if keys.0.len() > 1 {
+        line.push(Span::styled(")", HOTKEY_STYLE));
+    }
+}
+
+/// The keys bound to a command, e.g. `["j", "Down"]`, or just `"q"` in the config file.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(try_from = "KeyNames", into = "Vec<String>")]
+pub(crate) struct Keys(Vec<KeyCode>);
+impl TryFrom<KeyNames> for Keys {
+    type Error = color_eyre::Report;
+
+    fn try_from(names: KeyNames) -> Result<Self> {
+        let names = match names {
+            KeyNames::One(name) => vec![name],
+            KeyNames::Many(names) => names,
+        };
+        names
+            .iter()
+            .map(|name| parse_key(name))
+            .collect::<Result<_>>()
+            .map(Keys)
+    }
+}
+impl From<Keys> for Vec<String> {
+    fn from(keys: Keys) -> Self {
+        keys.0.into_iter().map(config_name).collect()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum KeyNames {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// The key named `name` in the config file: a character, `Enter`, `Esc`, `Backspace`, `Tab`,
+/// `Space`, `Home`, `End`, `PageUp`, `PageDown`, or an arrow as `Left` or `←`.
+fn parse_key(name: &str) -> Result<KeyCode> {
+    Ok(match name {
+        "Enter" => KeyCode::Enter,
+        "Esc" => KeyCode::Esc,
+        "Backspace" => KeyCode::Backspace,
+        "Tab" => KeyCode::Tab,
+        "Space" => KeyCode::Char(' '),
+        "Home" => KeyCode::Home,
+        "End" => KeyCode::End,
+        "PageUp" => KeyCode::PageUp,
+        "PageDown" => KeyCode::PageDown,
+        "Left" | "←" => KeyCode::Left,
+        "Right" | "→" => KeyCode::Right,
+        "Up" | "↑" => KeyCode::Up,
+        "Down" | "↓" => KeyCode::Down,
+        name => {
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => KeyCode::Char(c),
+                _ => return Err(eyre!("Unknown key '{name}'")),
+            }
+        }
+    })
+}
+
+/// `key` as [`parse_key`] reads it in the config file.
+fn config_name(key: KeyCode) -> String {
+    match key {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Esc => "Esc".to_string(),
+        KeyCode::Backspace => "Backspace".to_string(),
+        KeyCode::Tab => "Tab".to_string(),
+        KeyCode::Home => "Home".to_string(),
+        KeyCode::End => "End".to_string(),
+        KeyCode::PageUp => "PageUp".to_string(),
+        KeyCode::PageDown => "PageDown".to_string(),
+        KeyCode::Left => "Left".to_string(),
+        KeyCode::Right => "Right".to_string(),
+        KeyCode::Up => "Up".to_string(),
+        KeyCode::Down => "Down".to_string(),
+        // not bound by `parse_key`
+        key => key.to_string(),
+    }
+}
+
+/// `key` as block titles show it, arrows as arrows.
+fn key_name(key: KeyCode) -> String {
+    match key {
+        KeyCode::Left => "←".to_string(),
+        KeyCode::Right => "→".to_string(),
+        KeyCode::Up => "↑".to_string(),
+        KeyCode::Down => "↓".to_string(),
+        key => config_name(key),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::views::notes::NotesCommand;
+
+    #[test]
+    fn keys_bound_once() {
+        let keymap = toml::from_str::<Keymap<NotesCommand>>(r#"edit = ["e", "Enter"]"#).unwrap();
+        assert_eq!(keymap.command(KeyCode::Enter), Some(NotesCommand::Edit));
+
+        let err = toml::from_str::<Keymap<NotesCommand>>(
+            r#"
+            edit = ["e", "Enter"]
+            new = "Enter"
+            "#,
+        )
+        .unwrap_err();
+        assert!(
+            err.message()
+                .contains("Key 'Enter' is bound to both edit and new"),
+            "{err}"
+        );
+    }
+}
@@ -1,14 +1,63 @@
+mod chart {
+    pub(crate) mod axis;
+    pub(crate) mod export;
+    pub(crate) mod kind;
+    pub(crate) mod render;
+    pub(crate) mod text;
+    pub(crate) mod viewport;
+}
+mod cli;
+mod config;
 mod data {
+    pub(crate) mod bar;
+    pub(crate) mod csv;
+    #[allow(clippy::module_inception)]
     pub(crate) mod data;
+    pub(crate) mod provider;
+    pub(crate) mod resample;
+}
+mod hotkeys;
+mod keymap;
+mod indicators {
+    pub(crate) mod bands;
+    pub(crate) mod moving_average;
+    pub(crate) mod oscillators;
+    pub(crate) mod patterns;
+    pub(crate) mod pivots;
+    pub(crate) mod zones;
 }
+mod theme;
 mod views {
     pub(crate) mod instruments;
     pub(crate) mod notes;
 }
 
-use crate::{views::instruments::InstrumentList, views::notes::Notes};
-use color_eyre::{Result, eyre::Ok};
-use crossterm::event::{Event, EventStream, KeyEventKind};
+use crate::{
+    chart::{
+        export::{PixelSize, export_symbol, render_batch},
+        render::ChartSpec,
+        text::Renderer,
+    },
+    cli::{Cli, Command},
+    config::Config,
+    data::{
+        csv::CsvProvider,
+        data::StaticProvider,
+        provider::{DataProvider, Instrument},
+    },
+    theme::ThemeName,
+    views::instruments::{ChartImage, InstrumentList},
+    views::notes::Notes,
+};
+use clap::{Parser, ValueEnum};
+use color_eyre::{
+    Result,
+    eyre::{Ok, WrapErr, eyre},
+};
+use crossterm::{
+    ExecutableCommand,
+    event::{DisableMouseCapture, EnableMouseCapture, Event, EventStream, KeyEventKind},
+};
 use futures_util::FutureExt;
 use ratatui::{
     DefaultTerminal, Frame,
@@ -16,6 +65,9 @@ use ratatui::{
     style::{Modifier, Style},
 };
 use ratatui_image::picker::Picker;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
 use tokio::{
     self, sync::mpsc::{UnboundedSender, unbounded_channel},
 };
@@ -24,7 +76,11 @@ use tokio_stream::StreamExt;
 pub(crate) const HOTKEY_STYLE: ratatui::prelude::Style =
     Style::new().add_modifier(Modifier::REVERSED);
 
+/// The screens of the app, one at a time.
+#[derive(ValueEnum, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
 pub(crate) enum View {
+    #[default]
     Instruments,
     Notes,
 }
@@ -32,31 +88,78 @@ pub(crate) enum View {
 pub(crate) enum Action {
     Quit,
     RequestImageData,
+    /// result of a background chart job, see [`InstrumentList::on_action`]
+    ImageReady {
+        job_id: u64,
+        result: Result<Box<ChartImage>>,
+    },
     ChangeView(View),
+    /// switch both the views and the charts to the next built-in theme
+    NextTheme,
+    /// result of a chart export, the png and svg written, see [`InstrumentList::on_action`]
+    Exported(Result<[PathBuf; 2]>),
+    /// draw another frame with nothing else changed, e.g. the chart again after a frame without it
+    Redraw,
 }
 
 pub(crate) struct State {
     pub(crate) current_view: View,
     pub(crate) instruments: InstrumentList,
     pub(crate) notes: Notes,
+    pub(crate) theme: ThemeName,
     pub(crate) running: bool, // use to exit the app
 }
 
 impl State {
-    pub(crate) fn new(tx: UnboundedSender<Action>, picker: Picker) -> Self {
-        let state = State {
-            instruments: InstrumentList::new(tx, picker),
-            notes: Notes::new(),
-            current_view: View::Instruments,
+    pub(crate) fn new(
+        tx: UnboundedSender<Action>,
+        picker: Picker,
+        provider: Arc<dyn DataProvider>,
+        instruments: Vec<Instrument>,
+        spec: ChartSpec,
+        renderer: Renderer,
+        config: &Config,
+    ) -> Self {
+        State {
+            theme: spec.theme,
+            instruments: InstrumentList::new(
+                tx,
+                picker,
+                provider,
+                instruments,
+                spec,
+                renderer,
+                config,
+            ),
+            notes: Notes::new(config),
+            current_view: config.view.unwrap_or_default(),
             running: true,
-        };
-        state
+        }
     }
 }
 
-async fn run(terminal: &mut DefaultTerminal, picker: Picker) -> color_eyre::Result<()> {
+async fn run(
+    terminal: &mut DefaultTerminal,
+    picker: Picker,
+    provider: Arc<dyn DataProvider>,
+    spec: ChartSpec,
+    renderer: Renderer,
+    config: &Config,
+) -> color_eyre::Result<()> {
     let (tx, mut rx) = unbounded_channel::<Action>();
-    let mut state = State::new(tx.clone(), picker);
+    let instruments = provider.instruments().await?;
+    let mut state = State::new(
+        tx.clone(),
+        picker,
+        provider,
+        instruments,
+        spec,
+        renderer,
+        config,
+    );
+    if let Some(symbol) = &config.symbol {
+        state.instruments.select_symbol(symbol)?;
+    }
     let mut crossterm_event_stream = EventStream::new();
 
     loop {
@@ -83,22 +186,28 @@ async fn on_event(
     tx: &UnboundedSender<Action>,
 ) -> Result<()> {
     match maybe_event {
-        Some(std::result::Result::Ok(ev)) => {
-            // only handling key events for now
-            if let Event::Key(key_event) = ev {
-                if key_event.kind == KeyEventKind::Press {
-                    // delegate to the views
-                    match &mut state.current_view {
-                        View::Notes => {
-                            state.notes.on_event(key_event, tx).await?;
-                        }
-                        View::Instruments => {
-                            state.instruments.on_event(key_event, tx).await?;
-                        }
-                    }
+        Some(std::result::Result::Ok(Event::Key(key_event)))
+            if key_event.kind == KeyEventKind::Press =>
+        {
+            // delegate to the views
+            match &mut state.current_view {
+                View::Notes => {
+                    state.notes.on_event(key_event, tx).await?;
+                }
+                View::Instruments => {
+                    state.instruments.on_event(key_event, tx).await?;
                 }
             }
         }
+        Some(std::result::Result::Ok(Event::Mouse(mouse_event))) => match &mut state.current_view {
+            View::Notes => {
+                state.notes.on_mouse(mouse_event, tx).await?;
+            }
+            View::Instruments => {
+                state.instruments.on_mouse(mouse_event, tx).await?;
+            }
+        },
+        Some(std::result::Result::Ok(_)) => {}
         Some(Err(err)) => {
             return Err(err.into());
         }
@@ -128,19 +237,19 @@ async fn on_action(
                 return Ok(());
             }
         },
-        Some(specific_screen_action) => {
-            // delegate specific actions to the views
-            match &mut state.current_view {
-                View::Instruments => {
-                    state
-                        .instruments
-                        .on_action(Some(specific_screen_action))
-                        .await?;
-                }
-                View::Notes => {
-                    state.notes.on_action(Some(specific_screen_action)).await?;
-                }
-            }
+        Some(Action::NextTheme) => {
+            state.theme = state.theme.next();
+            state.instruments.set_theme(state.theme)?;
+        }
+        Some(Action::Redraw) => {}
+        Some(
+            image_action @ (Action::RequestImageData
+            | Action::ImageReady { .. }
+            | Action::Exported(_)),
+        ) => {
+            // chart jobs may be requested or complete while another view is shown,
+            // so these always go to the instruments view
+            state.instruments.on_action(Some(image_action)).await?;
         }
         None => {}
     }
@@ -152,17 +261,131 @@ async fn on_action(
 fn render(f: &mut Frame, state: &mut State) {
     let [my_area]: [Rect; 1] = Layout::vertical([Constraint::Fill(1)]).areas(f.area());
     match &mut state.current_view {
-        View::Instruments => state.instruments.render(f, my_area),
-        View::Notes => state.notes.render(f, my_area),
+        View::Instruments => state.instruments.render(f, my_area, state.theme.theme()),
+        View::Notes => state.notes.render(f, my_area, state.theme.theme()),
     }
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     color_eyre::install()?;
+    // before taking over the terminal, so `--help` and usage errors show
+    let cli = Cli::parse();
+    // command line and environment first, then the file, then the defaults
+    let config = cli
+        .settings
+        .or(Config::from_file(cli.config.as_deref())?)
+        .or(Config::defaults());
+    if cli.print_config {
+        print!("{}", toml::to_string(&config)?);
+        return Ok(());
+    }
+    let provider = data_provider(config.data_dir.clone());
+    let spec = chart_spec(&config);
+    if let Some(command) = cli.command {
+        let export_size = config.export_size.unwrap_or_default();
+        return run_command(command, provider, spec, export_size).await;
+    }
+
     let mut terminal = ratatui::init();
-    let picker = Picker::from_query_stdio()?;
-    run(&mut terminal, picker).await?;
+    let result = run_tui(&mut terminal, provider, spec, &config).await;
+    // restore whatever failed, and before reporting errors, so they are readable
+    let mouse_released = std::io::stdout()
+        .execute(DisableMouseCapture)
+        .map(|_| ())
+        .map_err(Into::into);
     ratatui::restore();
+    result.and(mouse_released)
+}
+
+/// The terminal ui in `terminal`, which is left for the caller to restore.
+async fn run_tui(
+    terminal: &mut DefaultTerminal,
+    provider: Arc<dyn DataProvider>,
+    spec: ChartSpec,
+    config: &Config,
+) -> Result<()> {
+    let picker = config.picker()?;
+    let renderer = config.renderer.unwrap_or_default().resolve(&picker);
+    std::io::stdout().execute(EnableMouseCapture)?;
+    run(terminal, picker, provider, spec, renderer, config).await
+}
+
+/// Bars from csv files when pointed to a directory, the built-in fixtures otherwise.
+fn data_provider(data_dir: Option<PathBuf>) -> Arc<dyn DataProvider> {
+    match data_dir {
+        Some(dir) => Arc::new(CsvProvider::new(dir)),
+        None => Arc::new(StaticProvider),
+    }
+}
+
+/// The default chart of the settings of `config`.
+fn chart_spec(config: &Config) -> ChartSpec {
+    let defaults = ChartSpec::default();
+    ChartSpec {
+        theme: config.theme.unwrap_or(defaults.theme),
+        timeframe: config.timeframe.unwrap_or(defaults.timeframe),
+        tz: config.tz.map_or(defaults.tz, |tz| tz.0),
+        moving_averages: config
+            .moving_averages
+            .clone()
+            .unwrap_or(defaults.moving_averages),
+        bands: config.bands.clone().unwrap_or(defaults.bands),
+        swing_strength: config.swing_strength.unwrap_or(defaults.swing_strength),
+        renko_box: config.renko_box.unwrap_or(defaults.renko_box),
+        ..defaults
+    }
+}
+
+/// Runs a subcommand of the [`Cli`] instead of the terminal ui.
+async fn run_command(
+    command: Command,
+    provider: Arc<dyn DataProvider>,
+    spec: ChartSpec,
+    export_size: PixelSize,
+) -> Result<()> {
+    let paths = match command {
+        Command::Export { symbol, size, out } => {
+            let instrument = instruments_of(provider.as_ref(), &[symbol])
+                .await?
+                .remove(0);
+            let (symbol, asset_class) = (instrument.symbol, instrument.asset_class);
+            let size = size.unwrap_or(export_size);
+            export_symbol(provider, symbol, asset_class, spec, size, out)
+                .await?
+                .to_vec()
+        }
+        Command::Render { symbols, size, out } => {
+            let instruments = instruments_of(provider.as_ref(), &symbols).await?;
+            std::fs::create_dir_all(&out)
+                .wrap_err_with(|| format!("Creating {}", out.display()))?;
+            let size = size.unwrap_or(export_size);
+            render_batch(provider, instruments, spec, size, out).await?
+        }
+    };
+    for path in paths {
+        println!("{}", path.display());
+    }
     Ok(())
 }
+
+/// The instruments of `provider` with `symbols`, in that order; all of them if there are none.
+async fn instruments_of(
+    provider: &dyn DataProvider,
+    symbols: &[String],
+) -> Result<Vec<Instrument>> {
+    let instruments = provider.instruments().await?;
+    if symbols.is_empty() {
+        return Ok(instruments);
+    }
+    symbols
+        .iter()
+        .map(|symbol| {
+            instruments
+                .iter()
+                .find(|instrument| instrument.symbol() == symbol)
+                .cloned()
+                .ok_or_else(|| eyre!("Unknown symbol '{symbol}'"))
+        })
+        .collect()
+}
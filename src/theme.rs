@@ -0,0 +1,212 @@
+use clap::ValueEnum;
+use plotters::style::RGBColor;
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+use strum_macros::Display;
+
+/// The built-in themes, cycled at runtime.
+#[derive(
+    Display, ValueEnum, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash,
+)]
+#[strum(serialize_all = "kebab-case")]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum ThemeName {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+    ColorblindSafe,
+}
+impl ThemeName {
+    /// The next theme, wrapping around to the first.
+    pub(crate) fn next(&self) -> Self {
+        match self {
+            ThemeName::Dark => ThemeName::Light,
+            ThemeName::Light => ThemeName::HighContrast,
+            ThemeName::HighContrast => ThemeName::ColorblindSafe,
+            ThemeName::ColorblindSafe => ThemeName::Dark,
+        }
+    }
+
+    pub(crate) fn theme(&self) -> &'static Theme {
+        match self {
+            ThemeName::Dark => &DARK,
+            ThemeName::Light => &LIGHT,
+            ThemeName::HighContrast => &HIGH_CONTRAST,
+            ThemeName::ColorblindSafe => &COLORBLIND_SAFE,
+        }
+    }
+}
+
+/// Colors and font of the charts (plotters) and of the views (ratatui).
+pub(crate) struct Theme {
+    pub(crate) background: RGBColor,
+    /// body and wick of candles closing at or above their open
+    pub(crate) up_body: RGBColor,
+    pub(crate) up_wick: RGBColor,
+    /// body and wick of candles closing below their open
+    pub(crate) down_body: RGBColor,
+    pub(crate) down_wick: RGBColor,
+    /// closes of the line and area charts
+    pub(crate) price_line: RGBColor,
+    pub(crate) axis: RGBColor,
+    pub(crate) label: RGBColor,
+    /// session gap markers
+    pub(crate) gap: RGBColor,
+    /// (family, size in pixels) of the chart labels
+    pub(crate) font: (&'static str, u32),
+    /// indicator lines, assigned in order and repeating
+    pub(crate) indicators: [RGBColor; 4],
+    /// band indicators (Bollinger, Keltner), assigned in order and repeating
+    pub(crate) bands: [RGBColor; 2],
+    /// legend box behind the indicator names
+    pub(crate) legend_bg: RGBColor,
+
+    /// instruments view border
+    pub(crate) accent: Color,
+    /// notes view border and items
+    pub(crate) notes: Color,
+    /// new note editor border
+    pub(crate) new_note: Color,
+    /// note editor border
+    pub(crate) edit_note: Color,
+    pub(crate) text: Color,
+    /// secondary text and separators
+    pub(crate) muted: Color,
+    pub(crate) header_fg: Color,
+    pub(crate) header_bg: Color,
+    pub(crate) error: Color,
+    /// bullish and bearish entries in lists, e.g. candlestick patterns
+    pub(crate) bullish: Color,
+    pub(crate) bearish: Color,
+}
+
+const DARK: Theme = Theme {
+    background: RGBColor(0, 0, 0),
+    up_body: RGBColor(38, 166, 154),
+    up_wick: RGBColor(38, 166, 154),
+    down_body: RGBColor(239, 83, 80),
+    down_wick: RGBColor(239, 83, 80),
+    price_line: RGBColor(79, 195, 247),
+    axis: RGBColor(255, 255, 255),
+    label: RGBColor(255, 255, 255),
+    gap: RGBColor(90, 90, 90),
+    font: ("sans-serif", 15),
+    indicators: [
+        RGBColor(255, 193, 7),
+        RGBColor(41, 121, 255),
+        RGBColor(224, 64, 251),
+        RGBColor(255, 255, 255),
+    ],
+    bands: [RGBColor(0, 188, 212), RGBColor(156, 204, 101)],
+    legend_bg: RGBColor(30, 30, 30),
+    accent: Color::LightYellow,
+    notes: Color::LightMagenta,
+    new_note: Color::LightGreen,
+    edit_note: Color::LightCyan,
+    text: Color::Gray,
+    muted: Color::DarkGray,
+    header_fg: Color::White,
+    header_bg: Color::DarkGray,
+    error: Color::LightRed,
+    bullish: Color::Rgb(38, 166, 154),
+    bearish: Color::Rgb(239, 83, 80),
+};
+
+const LIGHT: Theme = Theme {
+    background: RGBColor(250, 250, 250),
+    up_body: RGBColor(8, 153, 129),
+    up_wick: RGBColor(8, 153, 129),
+    down_body: RGBColor(242, 54, 69),
+    down_wick: RGBColor(242, 54, 69),
+    price_line: RGBColor(33, 150, 243),
+    axis: RGBColor(60, 60, 60),
+    label: RGBColor(30, 30, 30),
+    gap: RGBColor(190, 190, 190),
+    font: ("sans-serif", 15),
+    indicators: [
+        RGBColor(230, 126, 34),
+        RGBColor(41, 98, 255),
+        RGBColor(142, 36, 170),
+        RGBColor(0, 0, 0),
+    ],
+    bands: [RGBColor(0, 131, 143), RGBColor(85, 139, 47)],
+    legend_bg: RGBColor(235, 235, 235),
+    accent: Color::Rgb(160, 110, 0),
+    notes: Color::Magenta,
+    new_note: Color::Green,
+    edit_note: Color::Blue,
+    text: Color::Black,
+    muted: Color::Gray,
+    header_fg: Color::Black,
+    header_bg: Color::Gray,
+    error: Color::Red,
+    bullish: Color::Rgb(8, 153, 129),
+    bearish: Color::Rgb(242, 54, 69),
+};
+
+const HIGH_CONTRAST: Theme = Theme {
+    background: RGBColor(0, 0, 0),
+    up_body: RGBColor(0, 255, 0),
+    up_wick: RGBColor(255, 255, 255),
+    down_body: RGBColor(255, 0, 0),
+    down_wick: RGBColor(255, 255, 255),
+    price_line: RGBColor(255, 255, 255),
+    axis: RGBColor(255, 255, 255),
+    label: RGBColor(255, 255, 255),
+    gap: RGBColor(160, 160, 160),
+    font: ("sans-serif", 17),
+    indicators: [
+        RGBColor(255, 255, 0),
+        RGBColor(0, 255, 255),
+        RGBColor(255, 0, 255),
+        RGBColor(255, 255, 255),
+    ],
+    bands: [RGBColor(0, 128, 255), RGBColor(255, 128, 0)],
+    legend_bg: RGBColor(0, 0, 0),
+    accent: Color::White,
+    notes: Color::White,
+    new_note: Color::Yellow,
+    edit_note: Color::Cyan,
+    text: Color::White,
+    muted: Color::Gray,
+    header_fg: Color::Black,
+    header_bg: Color::White,
+    error: Color::Red,
+    bullish: Color::LightGreen,
+    bearish: Color::LightRed,
+};
+
+/// Blue/orange from the Okabe-Ito palette, distinguishable with the common color vision
+/// deficiencies.
+const COLORBLIND_SAFE: Theme = Theme {
+    background: RGBColor(0, 0, 0),
+    up_body: RGBColor(0, 114, 178),
+    up_wick: RGBColor(86, 180, 233),
+    down_body: RGBColor(230, 159, 0),
+    down_wick: RGBColor(240, 228, 66),
+    price_line: RGBColor(86, 180, 233),
+    axis: RGBColor(255, 255, 255),
+    label: RGBColor(255, 255, 255),
+    gap: RGBColor(90, 90, 90),
+    font: ("sans-serif", 15),
+    indicators: [
+        RGBColor(240, 228, 66),
+        RGBColor(204, 121, 167),
+        RGBColor(0, 158, 115),
+        RGBColor(255, 255, 255),
+    ],
+    bands: [RGBColor(86, 180, 233), RGBColor(213, 94, 0)],
+    legend_bg: RGBColor(30, 30, 30),
+    accent: Color::Rgb(230, 159, 0),
+    notes: Color::Rgb(204, 121, 167),
+    new_note: Color::Rgb(0, 158, 115),
+    edit_note: Color::Rgb(86, 180, 233),
+    text: Color::Gray,
+    muted: Color::DarkGray,
+    header_fg: Color::White,
+    header_bg: Color::DarkGray,
+    error: Color::Rgb(213, 94, 0),
+    bullish: Color::Rgb(86, 180, 233),
+    bearish: Color::Rgb(230, 159, 0),
+};
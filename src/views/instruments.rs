@@ -1,42 +1,147 @@
-use crate::data::data::{AssetClass, Symbol, get_data};
-use crate::{Action, HOTKEY_STYLE, View};
-use color_eyre::{Result, eyre::Ok};
-use crossterm::event::{KeyCode, KeyEvent};
-use image::{DynamicImage, ImageBuffer, RgbImage};
-use plotters::{
-    coord::types::{RangedCoordf32, RangedCoordi32},
-    prelude::*,
+use crate::chart::export::{PixelSize, export_symbol};
+use crate::chart::render::{ChartGeometry, ChartSpec, chart_image, readings};
+use crate::chart::text::{Renderer, TextChart};
+use crate::chart::viewport::Viewport;
+use crate::config::Config;
+use crate::data::bar::{Bar, Series};
+use crate::data::data::AssetClass;
+use crate::data::provider::{ALL_TIME, DataProvider, Instrument};
+use crate::data::resample::resample;
+use crate::indicators::oscillators::Oscillator;
+use crate::indicators::patterns::{Bias, Pattern, scan};
+use crate::indicators::zones::{MAX_ZONES, Zone, zones};
+use crate::keymap::{Command, Keymap};
+use crate::theme::{Theme, ThemeName};
+use crate::{
+    Action, View,
+    hotkeys::{HotkeyLine, HotkeyTitles},
 };
+use chrono::{DateTime, Utc};
+use color_eyre::{
+    Result,
+    eyre::{Ok, WrapErr, eyre},
+};
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use image::DynamicImage;
+use lru::LruCache;
 use ratatui::{
     Frame,
-    layout::{Constraint, Layout, Margin, Rect},
-    style::{Color, Style, Stylize},
+    layout::{Alignment, Constraint, Flex, Layout, Margin, Position, Rect},
+    style::{Style, Stylize},
     text::{Line, Span},
     widgets::{
-        Block, BorderType, Borders, Cell, Padding, Row, Scrollbar, ScrollbarOrientation,
-        ScrollbarState, Table, TableState,
+        Block, BorderType, Borders, Cell, Clear, List, ListItem, Padding, Paragraph, Row,
+        Scrollbar, ScrollbarOrientation, ScrollbarState, Table, TableState, Wrap,
     },
 };
-use ratatui_image::StatefulImage;
-use ratatui_image::picker::Picker;
+use ratatui_image::picker::{Picker, ProtocolType};
 use ratatui_image::protocol::StatefulProtocol;
-use std::str::FromStr;
-use strum::IntoEnumIterator;
-use tokio::sync::mpsc::UnboundedSender;
+use ratatui_image::{Resize, ResizeEncodeRender, StatefulImage};
+use serde::{Deserialize, Serialize};
+use std::num::NonZeroUsize;
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
+use strum_macros::Display;
+use tokio::{sync::mpsc::UnboundedSender, task::JoinHandle};
 
 const ITEM_HEIGHT: usize = 1;
+const CHART_CACHE_CAPACITY: NonZeroUsize = NonZeroUsize::new(32).unwrap();
+/// How many of the latest candlestick patterns the side panel lists.
+const RECENT_PATTERNS: usize = 20;
+/// Width of the pattern side panel in cells, enough for `dd HH:MM Three white soldiers`.
+const PATTERN_PANEL_WIDTH: u16 = 32;
+/// Height of the zone list below the table: top border, header and the zones.
+const ZONE_LIST_HEIGHT: u16 = MAX_ZONES as u16 + 2;
+/// Levels of the overview strip, lowest close to highest.
+const OVERVIEW_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+/// Status while a chart renders in the background.
+const RENDERING: &str = " rendering… ";
 
-pub struct Instrument {
-    symbol: String,
-    asset_class: AssetClass,
+/// What the keys do in the instruments view, `[keys.instruments]` in the config file.
+#[derive(Serialize, Deserialize, Display, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub(crate) enum InstrumentCommand {
+    Quit,
+    ShowNotes,
+    SelectNext,
+    SelectPrevious,
+    NextTimeframe,
+    NextChartKind,
+    NextScale,
+    ZoomIn,
+    ZoomOut,
+    PanLeft,
+    PanRight,
+    AllBars,
+    Crosshair,
+    ToggleVolume,
+    ToggleMovingAverages,
+    ToggleBands,
+    ToggleRsi,
+    ToggleMacd,
+    ToggleStochastic,
+    TogglePivots,
+    TogglePatterns,
+    ToggleZones,
+    NextTheme,
+    Export,
+    Reload,
+    ToggleDebug,
 }
-impl Instrument {
-    fn symbol(&self) -> &str {
-        &self.symbol
-    }
-    fn asset_class(&self) -> &str {
-        self.asset_class.as_ref()
-    }
+impl Command for InstrumentCommand {
+    const DEFAULTS: &'static [(Self, &'static [KeyCode])] = &[
+        (Self::Quit, &[KeyCode::Char('q')]),
+        (Self::ShowNotes, &[KeyCode::Char('N')]),
+        (Self::SelectNext, &[KeyCode::Char('j'), KeyCode::Down]),
+        (Self::SelectPrevious, &[KeyCode::Char('k'), KeyCode::Up]),
+        (Self::NextTimeframe, &[KeyCode::Char('t')]),
+        (Self::NextChartKind, &[KeyCode::Char('g')]),
+        (Self::NextScale, &[KeyCode::Char('y')]),
+        (Self::ZoomIn, &[KeyCode::Char('+'), KeyCode::Char('=')]),
+        (Self::ZoomOut, &[KeyCode::Char('-')]),
+        (Self::PanLeft, &[KeyCode::Left]),
+        (Self::PanRight, &[KeyCode::Right]),
+        (Self::AllBars, &[KeyCode::Char('0')]),
+        (Self::Crosshair, &[KeyCode::Char('x')]),
+        (Self::ToggleVolume, &[KeyCode::Char('v')]),
+        (Self::ToggleMovingAverages, &[KeyCode::Char('m')]),
+        (Self::ToggleBands, &[KeyCode::Char('b')]),
+        (Self::ToggleRsi, &[KeyCode::Char('R')]),
+        (Self::ToggleMacd, &[KeyCode::Char('M')]),
+        (Self::ToggleStochastic, &[KeyCode::Char('S')]),
+        (Self::TogglePivots, &[KeyCode::Char('p')]),
+        (Self::TogglePatterns, &[KeyCode::Char('c')]),
+        (Self::ToggleZones, &[KeyCode::Char('z')]),
+        (Self::NextTheme, &[KeyCode::Char('T')]),
+        (Self::Export, &[KeyCode::Char('e')]),
+        (Self::Reload, &[KeyCode::Char('r')]),
+        (Self::ToggleDebug, &[KeyCode::Char('D')]),
+    ];
+}
+
+/// What the keys do while the crosshair is on the chart, before the [`InstrumentCommand`]s,
+/// `[keys.crosshair]` in the config file.
+#[derive(Serialize, Deserialize, Display, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub(crate) enum CursorCommand {
+    Left,
+    Right,
+    Up,
+    Down,
+    Hide,
+}
+impl Command for CursorCommand {
+    const DEFAULTS: &'static [(Self, &'static [KeyCode])] = &[
+        (Self::Left, &[KeyCode::Left, KeyCode::Char('h')]),
+        (Self::Right, &[KeyCode::Right, KeyCode::Char('l')]),
+        (Self::Up, &[KeyCode::Up, KeyCode::Char('k')]),
+        (Self::Down, &[KeyCode::Down, KeyCode::Char('j')]),
+        (Self::Hide, &[KeyCode::Esc, KeyCode::Char('x')]),
+    ];
 }
 
 pub struct InstrumentList {
@@ -44,45 +149,168 @@ pub struct InstrumentList {
     state: TableState,
     scroll_state: ScrollbarState,
     picker: Picker,
+    /// images or text, [resolved](Renderer::resolve) already
+    renderer: Renderer,
+    provider: Arc<dyn DataProvider>,
+    /// how charts are drawn, see [`ChartSpec`]
+    spec: ChartSpec,
+    tx: UnboundedSender<Action>,
+    /// encoded charts, so revisiting a symbol does not redraw it
+    cache: ChartCache,
+    /// key of the chart currently on screen, kept while a newer one is rendering
+    displayed: Option<ChartKey>,
+    /// crosshair over the displayed chart, `None` outside of cursor mode
+    cursor: Option<Cursor>,
+    show_debug: bool,
+    /// leave the chart out of the next frame, to erase cells drawn over it before drawing it again
+    blank_chart: bool,
+    keymap: Keymap<InstrumentCommand>,
+    /// keys of cursor mode, tried before the `keymap`
+    cursor_keymap: Keymap<CursorCommand>,
+    /// width and height of the charts exported with `e`
+    export_size: PixelSize,
+    /// outcome of the latest export, shown until the next key press
+    status: Option<std::result::Result<String, String>>,
+    /// why the chart for the current selection could not be drawn, e.g. a malformed csv row
+    error: Option<String>,
+    /// area the chart was last requested for, `None` until the first frame
+    image_area: Option<Rect>,
+    /// area of the table as last rendered, for clicks on its rows
+    table_area: Option<Rect>,
+    /// column the chart was last panned to while dragging it, `None` when not dragging
+    drag_column: Option<u16>,
+    hotkeys: HotkeyTitles,
+    /// id of the most recently spawned chart job; older jobs bail out when they see a newer id
+    latest_job: Arc<AtomicU64>,
+    job: Option<JoinHandle<()>>,
+    rendering: bool,
 }
 impl InstrumentList {
-    pub(crate) fn new(_tx: UnboundedSender<Action>, picker: Picker) -> Self {
-        let instruments = Symbol::iter()
-            .map(|v| Instrument {
-                symbol: v.to_string(),
-                asset_class: v.asset_class(),
-            })
-            .collect::<Vec<_>>();
+    pub(crate) fn new(
+        tx: UnboundedSender<Action>,
+        picker: Picker,
+        provider: Arc<dyn DataProvider>,
+        instruments: Vec<Instrument>,
+        spec: ChartSpec,
+        renderer: Renderer,
+        config: &Config,
+    ) -> Self {
         Self {
             state: TableState::default().with_selected(0),
-            scroll_state: ScrollbarState::new((instruments.len() - 1) * ITEM_HEIGHT),
+            scroll_state: ScrollbarState::new(instruments.len().saturating_sub(1) * ITEM_HEIGHT),
             instruments,
             picker,
+            renderer,
+            provider,
+            spec,
+            tx,
+            cache: ChartCache::new(CHART_CACHE_CAPACITY),
+            displayed: None,
+            cursor: None,
+            show_debug: false,
+            blank_chart: false,
+            keymap: config.keys.instruments.clone(),
+            cursor_keymap: config.keys.crosshair.clone(),
+            export_size: config.export_size.unwrap_or_default(),
+            status: None,
+            error: None,
+            image_area: None,
+            table_area: None,
+            drag_column: None,
+            hotkeys: HotkeyTitles::default(),
+            latest_job: Arc::new(AtomicU64::new(0)),
+            job: None,
+            rendering: false,
         }
     }
 
-    pub(crate) fn render(&mut self, f: &mut Frame<'_>, main_area: Rect) {
-        // outer block
+    pub(crate) fn render(&mut self, f: &mut Frame<'_>, main_area: Rect, theme: &Theme) {
+        // outer block, its titles list the hotkeys
+        let info = HotkeyLine::from(
+            Line::from(format!(
+                " Instruments (price action snapshots) ── {} ── {} ── {} ",
+                self.spec.timeframe,
+                self.spec.kind,
+                self.scale_title()
+            ))
+            .left_aligned(),
+        );
+        let mut views = self.keymap.hints(
+            &[
+                (&[InstrumentCommand::ShowNotes], "Notes"),
+                (&[InstrumentCommand::Quit], "quit"),
+            ],
+            u16::MAX,
+        );
+        views.push(" ");
+        let views = views.alignment(Alignment::Right);
+        let status = HotkeyLine::from(
+            match (&self.status, self.rendering) {
+                (_, true) => Line::from(RENDERING),
+                (Some(std::result::Result::Ok(status)), false) => Line::from(format!(" {status} ")),
+                (Some(Err(err)), false) => {
+                    Line::styled(format!(" {err} "), Style::new().fg(theme.error))
+                }
+                (None, false) => Line::default(),
+            }
+            .right_aligned(),
+        );
+        // the hints stop short of the status, and of the longest one while rendering
+        let hints_width = main_area
+            .width
+            .saturating_sub(2)
+            .saturating_sub(status.width().max(Line::from(RENDERING).width() as u16) + 1);
+        let hints = self.keymap.hints(
+            &[
+                (
+                    &[
+                        InstrumentCommand::SelectNext,
+                        InstrumentCommand::SelectPrevious,
+                    ],
+                    "select",
+                ),
+                (&[InstrumentCommand::NextTimeframe], "timeframe"),
+                (&[InstrumentCommand::NextChartKind], "graph"),
+                (&[InstrumentCommand::NextScale], "y-axis"),
+                (
+                    &[InstrumentCommand::ZoomIn, InstrumentCommand::ZoomOut],
+                    "zoom",
+                ),
+                (
+                    &[InstrumentCommand::PanLeft, InstrumentCommand::PanRight],
+                    "pan",
+                ),
+                (&[InstrumentCommand::AllBars], "all bars"),
+                (&[InstrumentCommand::Crosshair], "crosshair"),
+                (&[InstrumentCommand::ToggleVolume], "volume"),
+                (
+                    &[InstrumentCommand::ToggleMovingAverages],
+                    "moving averages",
+                ),
+                (&[InstrumentCommand::ToggleBands], "bands"),
+                (&[InstrumentCommand::ToggleRsi], "RSI"),
+                (&[InstrumentCommand::ToggleMacd], "MACD"),
+                (&[InstrumentCommand::ToggleStochastic], "Stochastic"),
+                (&[InstrumentCommand::TogglePivots], "pivots"),
+                (&[InstrumentCommand::TogglePatterns], "candle patterns"),
+                (&[InstrumentCommand::ToggleZones], "zones"),
+                (&[InstrumentCommand::NextTheme], "Theme"),
+                (&[InstrumentCommand::Export], "export"),
+                (&[InstrumentCommand::Reload], "reload"),
+            ],
+            hints_width,
+        );
+        self.hotkeys.clear();
+        self.hotkeys.top(main_area, &[&info, &views]);
+        self.hotkeys.bottom(main_area, &[&hints, &status]);
+
         let outer_block = Block::bordered()
             .border_type(BorderType::Rounded)
-            .border_style(Style::new().fg(Color::LightYellow))
-            .title(Line::from(" Instruments (price action snapshots)").left_aligned())
-            .title(
-                Line::from(vec![
-                    Span::styled("N", HOTKEY_STYLE),
-                    "otes──".into(),
-                    Span::styled("q", HOTKEY_STYLE),
-                    "uit ".into(),
-                ])
-                .right_aligned(),
-            )
-            .title_bottom(
-                Line::from(vec![
-                    Span::styled("j(↓)/h(↑)", HOTKEY_STYLE),
-                    "(select)".into(),
-                ])
-                .left_aligned(),
-            )
+            .border_style(Style::new().fg(theme.accent))
+            .title(info.line)
+            .title(views.line)
+            .title_bottom(hints.line)
+            .title_bottom(status.line)
             .padding(Padding::uniform(1));
 
         f.render_widget(&outer_block, main_area);
@@ -91,8 +319,21 @@ impl InstrumentList {
         let [table_area, image_area]: [Rect; 2] =
             Layout::horizontal([Constraint::Percentage(25), Constraint::Percentage(75)])
                 .areas(outer_block.inner(main_area));
+        let [table_area, zones_area]: [Rect; 2] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(if self.spec.show_zones {
+                ZONE_LIST_HEIGHT
+            } else {
+                0
+            }),
+        ])
+        .areas(table_area);
+        if self.spec.show_zones {
+            self.render_zones(f, zones_area, theme);
+        }
 
         // table
+        self.table_area = Some(table_area);
         f.render_stateful_widget(
             Table::default()
                 .widths([
@@ -101,10 +342,14 @@ impl InstrumentList {
                     Constraint::Length(12),
                 ])
                 .column_spacing(1)
-                .style(Style::new().gray())
+                .style(Style::new().fg(theme.text))
                 .header(
-                    Row::new(vec!["Ix", "Symbol", "Asset Class"])
-                        .style(Style::default().bg(Color::DarkGray).fg(Color::White).bold()),
+                    Row::new(vec!["Ix", "Symbol", "Asset Class"]).style(
+                        Style::default()
+                            .bg(theme.header_bg)
+                            .fg(theme.header_fg)
+                            .bold(),
+                    ),
                 )
                 .rows(
                     self.instruments
@@ -112,7 +357,7 @@ impl InstrumentList {
                         .enumerate()
                         .map(|(i, item)| {
                             Row::new([
-                                Cell::new(i.to_string()).style(Color::DarkGray),
+                                Cell::new(i.to_string()).style(theme.muted),
                                 Cell::new(item.symbol()),
                                 Cell::new(item.asset_class()),
                             ])
@@ -122,14 +367,14 @@ impl InstrumentList {
                 // hack: empty footer, to fix scrollbar 'thumb' not visible on last row
                 .footer(
                     Row::new([Cell::default(), Cell::default()])
-                        .style(Style::default().bg(Color::DarkGray)),
+                        .style(Style::default().bg(theme.header_bg)),
                 )
                 .row_highlight_style(Style::new().reversed())
                 .block(
                     Block::new()
                         .borders(Borders::RIGHT)
                         .border_type(BorderType::Plain)
-                        .border_style(Style::new().dark_gray()),
+                        .border_style(Style::new().fg(theme.muted)),
                 ),
             table_area,
             &mut self.state,
@@ -148,10 +393,32 @@ impl InstrumentList {
             &mut self.scroll_state,
         );
 
-        // let stateful_image: StatefulImage<StatefulProtocol> = StatefulImage::default();
-        // f.render_stateful_widget(stateful_image, image_area, &mut self.img_protocol);
+        // candlestick patterns of the displayed chart, right of it
+        let [image_area, patterns_area]: [Rect; 2] = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Length(if self.spec.show_patterns {
+                PATTERN_PANEL_WIDTH
+            } else {
+                0
+            }),
+        ])
+        .areas(image_area);
+        if self.spec.show_patterns {
+            self.render_patterns(f, patterns_area, theme);
+        }
+
+        // where the chart sits in the whole history, below it
+        let [image_area, overview_area]: [Rect; 2] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(image_area);
+        self.render_overview(f, overview_area, theme);
 
-        self.render_image(f, image_area).expect("Failed to render image");
+        if std::mem::take(&mut self.blank_chart) {
+            f.render_widget(Clear, image_area);
+            return;
+        }
+        self.render_image(f, image_area, theme)
+            .expect("Failed to render image");
+        self.render_crosshair(f, image_area, theme);
     }
 
     pub(crate) async fn on_event(
@@ -159,36 +426,330 @@ impl InstrumentList {
         key_event: KeyEvent,
         tx: &UnboundedSender<Action>,
     ) -> Result<()> {
-        match key_event.code {
-            KeyCode::Char('q') => tx.send(Action::Quit)?,
-            KeyCode::Char('j') | KeyCode::Down => {
+        self.status = None;
+        let cursor_command = self
+            .cursor_keymap
+            .command(key_event.code)
+            .filter(|_| self.cursor.is_some());
+        if let Some(command) = cursor_command {
+            return self.move_cursor(command);
+        }
+        let Some(command) = self.keymap.command(key_event.code) else {
+            return Ok(());
+        };
+        match command {
+            InstrumentCommand::Quit => tx.send(Action::Quit)?,
+            InstrumentCommand::Crosshair => {
+                self.cursor = self.displayed_chart().map(|chart| Cursor {
+                    ix: chart.geometry.bar_x.len().saturating_sub(1),
+                    row: None,
+                })
+            }
+            InstrumentCommand::SelectNext => {
                 self.next_row().await?;
                 tx.send(Action::RequestImageData)?;
             }
-            KeyCode::Char('k') | KeyCode::Up => {
+            InstrumentCommand::SelectPrevious => {
                 self.previous_row().await?;
                 tx.send(Action::RequestImageData)?;
             }
-            KeyCode::Char('N') => tx.send(Action::ChangeView(View::Notes))?,
-            _ => {}
+            InstrumentCommand::Reload => {
+                // reload: forget every encoded chart, e.g. after the underlying data changed
+                self.cache.clear();
+                self.displayed = None;
+                tx.send(Action::RequestImageData)?;
+            }
+            InstrumentCommand::NextTimeframe => {
+                self.spec.timeframe = self.spec.timeframe.next();
+                tx.send(Action::RequestImageData)?;
+            }
+            InstrumentCommand::NextChartKind => {
+                self.spec.kind = self.spec.kind.next();
+                tx.send(Action::RequestImageData)?;
+            }
+            InstrumentCommand::NextScale => {
+                self.spec.scale = self.spec.scale.next();
+                tx.send(Action::RequestImageData)?;
+            }
+            InstrumentCommand::ZoomIn => self.change_viewport(Viewport::zoom_in)?,
+            InstrumentCommand::ZoomOut => self.change_viewport(Viewport::zoom_out)?,
+            InstrumentCommand::PanLeft => self.change_viewport(Viewport::pan_left)?,
+            InstrumentCommand::PanRight => self.change_viewport(Viewport::pan_right)?,
+            InstrumentCommand::AllBars if !self.spec.viewport.is_all() => {
+                self.spec.viewport = Viewport::default();
+                tx.send(Action::RequestImageData)?;
+            }
+            InstrumentCommand::AllBars => {}
+            InstrumentCommand::ToggleVolume => {
+                self.spec.show_volume = !self.spec.show_volume;
+                tx.send(Action::RequestImageData)?;
+            }
+            InstrumentCommand::ToggleMovingAverages => {
+                self.spec.show_moving_averages = !self.spec.show_moving_averages;
+                tx.send(Action::RequestImageData)?;
+            }
+            InstrumentCommand::ToggleBands => {
+                self.spec.show_bands = !self.spec.show_bands;
+                tx.send(Action::RequestImageData)?;
+            }
+            InstrumentCommand::ToggleRsi => self.toggle_oscillator(Oscillator::Rsi)?,
+            InstrumentCommand::ToggleMacd => self.toggle_oscillator(Oscillator::Macd)?,
+            InstrumentCommand::ToggleStochastic => {
+                self.toggle_oscillator(Oscillator::Stochastic)?
+            }
+            InstrumentCommand::TogglePivots => {
+                self.spec.show_pivots = !self.spec.show_pivots;
+                tx.send(Action::RequestImageData)?;
+            }
+            InstrumentCommand::TogglePatterns => {
+                self.spec.show_patterns = !self.spec.show_patterns;
+                tx.send(Action::RequestImageData)?;
+            }
+            InstrumentCommand::ToggleZones => {
+                self.spec.show_zones = !self.spec.show_zones;
+                tx.send(Action::RequestImageData)?;
+            }
+            InstrumentCommand::NextTheme => tx.send(Action::NextTheme)?,
+            InstrumentCommand::Export => self.export()?,
+            InstrumentCommand::ToggleDebug => self.show_debug = !self.show_debug,
+            InstrumentCommand::ShowNotes => tx.send(Action::ChangeView(View::Notes))?,
         };
         Ok(())
     }
 
+    /// Clicks press the hotkeys in the titles and select table rows, the wheel scrolls the table
+    /// and zooms the chart, dragging the chart pans it.
+    pub(crate) async fn on_mouse(
+        &mut self,
+        mouse_event: MouseEvent,
+        tx: &UnboundedSender<Action>,
+    ) -> Result<()> {
+        let position = Position::new(mouse_event.column, mouse_event.row);
+        let on_table = self.table_area.is_some_and(|area| area.contains(position));
+        let on_chart = self.image_area.is_some_and(|area| area.contains(position));
+        match mouse_event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                if let Some(key) = self.hotkeys.key_at(position) {
+                    return self.on_event(KeyEvent::from(key), tx).await;
+                }
+                if on_table {
+                    self.click_row(position.y)?;
+                }
+                self.drag_column = on_chart.then_some(position.x);
+            }
+            MouseEventKind::Drag(MouseButton::Left) => {
+                if let Some(column) = self.drag_column {
+                    self.drag(column, position.x)?;
+                }
+            }
+            MouseEventKind::Up(MouseButton::Left) => self.drag_column = None,
+            // the wheel stops at the ends of the table rather than cycling through it
+            MouseEventKind::ScrollDown
+                if on_table
+                    && self.state.selected() < Some(self.instruments.len().saturating_sub(1)) =>
+            {
+                self.next_row().await?;
+                tx.send(Action::RequestImageData)?;
+            }
+            MouseEventKind::ScrollUp if on_table && self.state.selected() > Some(0) => {
+                self.previous_row().await?;
+                tx.send(Action::RequestImageData)?;
+            }
+            MouseEventKind::ScrollUp if on_chart => self.change_viewport(Viewport::zoom_in)?,
+            MouseEventKind::ScrollDown if on_chart => self.change_viewport(Viewport::zoom_out)?,
+            _ => {}
+        }
+        Ok(())
+    }
+
     pub(crate) async fn on_action(&mut self, action: Option<Action>) -> Result<()> {
         if let Some(action) = action {
             match action {
-                Action::RequestImageData => {/* todo: offload image state init to another thread */},
-                _ => (), // 'Quit' and 'ChangeView' are handled in main run loop
+                Action::RequestImageData => self.request_image().await?,
+                Action::ImageReady { job_id, result }
+                    if job_id == self.latest_job.load(Ordering::SeqCst) =>
+                {
+                    self.job = None;
+                    self.rendering = false;
+                    match result {
+                        std::result::Result::Ok(chart) => {
+                            self.displayed = Some(chart.key.clone());
+                            let cached = CachedChart {
+                                protocol: chart.protocol,
+                                geometry: chart.geometry,
+                                details: chart.details,
+                            };
+                            self.cache.entries.put(chart.key, cached);
+                        }
+                        // keep the app running, the data of other symbols may be fine
+                        Err(err) => {
+                            self.displayed = None;
+                            self.error = Some(format!("{err:#}"));
+                        }
+                    }
+                }
+                Action::Exported(result) => {
+                    self.status = Some(match result {
+                        std::result::Result::Ok(paths) => std::result::Result::Ok(format!(
+                            "exported {}",
+                            paths.map(|path| path.display().to_string()).join(", ")
+                        )),
+                        Err(err) => Err(format!("export failed: {err:#}")),
+                    })
+                }
+                _ => (), // 'Quit' and 'ChangeView' are handled in main run loop, stale images dropped
             }
         }
         Ok(())
     }
 
+    /// Selects the instrument with `symbol`, e.g. the one to start with.
+    pub(crate) fn select_symbol(&mut self, symbol: &str) -> Result<()> {
+        let ix = self
+            .instruments
+            .iter()
+            .position(|instrument| instrument.symbol() == symbol)
+            .ok_or_else(|| eyre!("Unknown symbol '{symbol}'"))?;
+        self.state.select(Some(ix));
+        self.scroll_state = self.scroll_state.position(ix * ITEM_HEIGHT);
+        self.tx.send(Action::RequestImageData)?;
+        Ok(())
+    }
+
+    /// Redraws the chart in `theme`, cached charts of the previous theme stay valid.
+    pub(crate) fn set_theme(&mut self, theme: ThemeName) -> Result<()> {
+        self.spec.theme = theme;
+        self.tx.send(Action::RequestImageData)?;
+        Ok(())
+    }
+
+    /// Moves the crosshair by a bar left or right, by a row up or down, or hides it.
+    fn move_cursor(&mut self, command: CursorCommand) -> Result<()> {
+        let bars = self
+            .displayed_chart()
+            .map(|chart| chart.geometry.bar_x.len())
+            .unwrap_or_default();
+        let row = self.cursor_row();
+        let Some(cursor) = self.cursor.as_mut() else {
+            return Ok(());
+        };
+        // the chart may have fewer bars by now, e.g. after zooming in
+        cursor.ix = cursor.ix.min(bars.saturating_sub(1));
+        match command {
+            CursorCommand::Left => cursor.ix = cursor.ix.saturating_sub(1),
+            CursorCommand::Right => cursor.ix = (cursor.ix + 1).min(bars.saturating_sub(1)),
+            CursorCommand::Up => cursor.row = row.map(|row| row.saturating_sub(1)),
+            CursorCommand::Down => cursor.row = row.map(|row| row + 1),
+            CursorCommand::Hide => self.cursor = None,
+        }
+        // the cells of the previous position still cover the chart, unlike with halfblocks: a
+        // frame without the chart erases them, the next one draws it again
+        if self.renderer == Renderer::Image
+            && self.picker.protocol_type() != ProtocolType::Halfblocks
+        {
+            self.blank_chart = true;
+            self.tx.send(Action::Redraw)?;
+        }
+        Ok(())
+    }
+
+    /// Zooms or pans by `change`, within the bars of the displayed chart; nothing to move before
+    /// the first chart is there.
+    fn change_viewport(&mut self, change: impl FnOnce(&mut Viewport, usize)) -> Result<()> {
+        let Some(len) = self.displayed_details().map(|details| details.closes.len()) else {
+            return Ok(());
+        };
+        let viewport = self.spec.viewport;
+        change(&mut self.spec.viewport, len);
+        if self.spec.viewport != viewport {
+            self.tx.send(Action::RequestImageData)?;
+        }
+        Ok(())
+    }
+
+    /// Selects the instrument in terminal row `row` of the table, if there is one.
+    fn click_row(&mut self, row: u16) -> Result<()> {
+        let Some(area) = self.table_area else {
+            return Ok(());
+        };
+        // below the header, above the footer
+        let rows = area.y + 1..area.bottom().saturating_sub(1);
+        if !rows.contains(&row) {
+            return Ok(());
+        }
+        let ix = self.state.offset() + ((row - rows.start) as usize) / ITEM_HEIGHT;
+        if ix < self.instruments.len() && self.state.selected() != Some(ix) {
+            self.state.select(Some(ix));
+            self.scroll_state = self.scroll_state.position(ix * ITEM_HEIGHT);
+            self.tx.send(Action::RequestImageData)?;
+        }
+        Ok(())
+    }
+
+    /// Pans the chart dragged from terminal column `from` to `to` along with the mouse: dragging
+    /// right brings older bars into view.
+    fn drag(&mut self, from: u16, to: u16) -> Result<()> {
+        let Some(bar_x) = self
+            .displayed_chart()
+            .map(|chart| &chart.geometry.bar_x)
+            .filter(|bar_x| bar_x.len() > 1)
+        else {
+            return Ok(());
+        };
+        let bar_width = (bar_x[bar_x.len() - 1] - bar_x[0]) as f32 / (bar_x.len() - 1) as f32;
+        let font_width = self.picker.font_size().0.max(1) as f32;
+        let bars = ((to as f32 - from as f32) * font_width / bar_width.max(1.0)) as isize;
+        // a part of a bar is kept for the next move
+        if bars != 0 {
+            self.drag_column = Some(to);
+            self.change_viewport(|viewport, len| viewport.pan(len, bars))?;
+        }
+        Ok(())
+    }
+
+    /// Writes the displayed chart, as it is on screen but at the export size, to png and svg
+    /// files in the working directory. In the background, see [`Action::Exported`].
+    fn export(&mut self) -> Result<()> {
+        let Some(key) = self.displayed.clone() else {
+            return Ok(()); // nothing on screen yet
+        };
+        let Some(asset_class) = self
+            .instruments
+            .iter()
+            .find(|instrument| instrument.symbol() == key.symbol)
+            .map(|instrument| instrument.asset_class)
+        else {
+            return Ok(());
+        };
+        let dir = std::env::current_dir()?;
+        let provider = self.provider.clone();
+        let size = self.export_size;
+        let tx = self.tx.clone();
+        self.status = Some(std::result::Result::Ok(format!(
+            "exporting {}…",
+            key.symbol
+        )));
+        tokio::spawn(async move {
+            let result =
+                export_symbol(provider, key.symbol, asset_class, key.spec, size, dir).await;
+            let _ = tx.send(Action::Exported(result));
+        });
+        Ok(())
+    }
+
+    /// Shows the pane of `oscillator` if hidden, hides it otherwise.
+    fn toggle_oscillator(&mut self, oscillator: Oscillator) -> Result<()> {
+        if !self.spec.oscillators.remove(&oscillator) {
+            self.spec.oscillators.insert(oscillator);
+        }
+        self.tx.send(Action::RequestImageData)?;
+        Ok(())
+    }
+
     async fn next_row(&mut self) -> Result<()> {
         let i = match self.state.selected() {
             Some(i) => {
-                if i >= self.instruments.len() - 1 {
+                if i >= self.instruments.len().saturating_sub(1) {
                     // cycle through the list
                     0
                 } else {
@@ -206,7 +767,7 @@ impl InstrumentList {
         let i = match self.state.selected() {
             Some(i) => {
                 if i == 0 {
-                    self.instruments.len() - 1
+                    self.instruments.len().saturating_sub(1)
                 } else {
                     i - 1
                 }
@@ -218,91 +779,646 @@ impl InstrumentList {
         Ok(())
     }
 
-    fn render_image(&self, f: &mut Frame<'_>, image_area: Rect) -> Result<()> {
-        let (cell_width_px, cell_height_px) = self.picker.font_size();
-        // let (width, height) = (600u32, 400u32);
-        let (width, height) = cell_rect_to_pixel_size(&image_area, (cell_width_px, cell_height_px));
-        let mut img_buf = vec![0u8; width as usize * height as usize * 3]; // RGB pixel format
-        let root = BitMapBackend::with_buffer(&mut img_buf, (width as u32, height as u32))
-            .into_drawing_area();
-        root.fill(&BLACK)?;
-
-        if let Some(selected) = self.state.selected() {
-            if let Some(instrument) = self.instruments.get(selected) {
-                let symbol = Symbol::from_str(instrument.symbol.as_str())?;
-                let data = get_data(symbol);
-
-                let (_, o, h, l, c) = data.first().unwrap();
-                let (mut y_min, mut y_max) = (o.min(*h).min(*l).min(*c), o.max(*h).max(*l).max(*c));
-                (y_min, y_max) =
-                    data.iter()
-                        .skip(1)
-                        .fold((y_min, y_max), |(y_min, y_max), (_, o, h, l, c)| {
-                            (
-                                y_min.min(*o).min(*h).min(*l).min(*c),
-                                y_max.max(*o).max(*h).max(*l).max(*c),
-                            )
-                        });
-
-                let mut chart: ChartContext<
-                    '_,
-                    BitMapBackend<'_>,
-                    Cartesian2d<RangedCoordi32, RangedCoordf32>,
-                > = ChartBuilder::on(&root)
-                    .x_label_area_size(25)
-                    .right_y_label_area_size(45)
-                    .build_cartesian_2d(-1 as i32..data.len() as i32, y_min..y_max)?;
-
-                chart
-                    .configure_mesh()
-                    .disable_x_mesh()
-                    .disable_y_mesh()
-                    .axis_style(ShapeStyle {
-                        color: plotters::style::Color::to_rgba(&WHITE),
-                        filled: false,
-                        stroke_width: 1,
-                    })
-                    .disable_x_axis()
-                    .y_label_style(
-                        TextStyle::from(("sans-serif", 15).into_font())
-                            .color(&plotters::style::Color::to_rgba(&WHITE)),
-                    )
-                    .draw()?;
-
-                chart.draw_series(data.iter().enumerate().map(|(ix, x)| {
-                    CandleStick::new(
-                        ix as i32,
-                        x.1,
-                        x.2,
-                        x.3,
-                        x.4,
-                        plotters::style::Color::filled(&WHITE),
-                        WHITE,
-                        7,
-                    )
-                }))?;
-
-                // manually call the present function to avoid the IO failure being ignored silently
-                root.present()
-                    .expect("Failed to draw chart result to backend");
-
-                drop(chart); // to release the mutable borrow of buff
-                drop(root); // also to release the mutable borrow of buff
-
-                let rgb_img: RgbImage = ImageBuffer::from_raw(width as u32, height as u32, img_buf)
-                    .expect("Failed to construct ImageBuffer");
-                let mut stateful_protocol = self
-                    .picker
-                    .new_resize_protocol(DynamicImage::ImageRgb8(rgb_img));
-                let stateful_image: StatefulImage<StatefulProtocol> = StatefulImage::default();
-                f.render_stateful_widget(stateful_image, image_area, &mut stateful_protocol);
+    /// Shows the last completed chart for `image_area`, or a placeholder while the background job
+    /// for the current selection and area is still running.
+    fn render_image(&mut self, f: &mut Frame<'_>, image_area: Rect, theme: &Theme) -> Result<()> {
+        if self.image_area != Some(image_area) {
+            // first frame or terminal resized: cached charts have the wrong size now
+            self.image_area = Some(image_area);
+            self.cache.clear();
+            self.displayed = None;
+            self.tx.send(Action::RequestImageData)?;
+        }
+
+        let displayed = self.displayed.as_ref();
+        match displayed.and_then(|key| Some((key, self.cache.entries.peek_mut(key)?))) {
+            Some((
+                _,
+                CachedChart {
+                    protocol: Some(protocol),
+                    ..
+                },
+            )) => {
+                f.render_stateful_widget(StatefulImage::default(), image_area, protocol);
+            }
+            Some((
+                key,
+                CachedChart {
+                    protocol: None,
+                    details,
+                    ..
+                },
+            )) => {
+                if let std::result::Result::Ok(chart) =
+                    TextChart::new(&details.bars, key.spec.kind, key.spec.scale, theme)
+                {
+                    f.render_widget(chart, image_area);
+                }
+            }
+            None if self.error.is_some() => {
+                f.render_widget(
+                    Paragraph::new(self.error.as_deref().unwrap_or_default())
+                        .style(Style::new().fg(theme.error))
+                        .wrap(Wrap { trim: true }),
+                    image_area,
+                );
+            }
+            None => {
+                let [placeholder_area]: [Rect; 1] = Layout::vertical([Constraint::Length(1)])
+                    .flex(Flex::Center)
+                    .areas(image_area);
+                f.render_widget(
+                    Paragraph::new("rendering…")
+                        .style(Style::new().fg(theme.muted))
+                        .centered(),
+                    placeholder_area,
+                );
+            }
+        }
+
+        if self.show_debug {
+            let text = format!(
+                "cache {}/{}  hits {}  misses {}",
+                self.cache.entries.len(),
+                self.cache.entries.cap(),
+                self.cache.hits,
+                self.cache.misses,
+            );
+            let overlay_area = Rect {
+                width: (text.chars().count() as u16 + 2).min(image_area.width),
+                height: 1.min(image_area.height),
+                ..image_area
+            };
+            f.render_widget(Clear, overlay_area);
+            f.render_widget(
+                Paragraph::new(format!(" {text} ")).style(Style::new().black().on_yellow()),
+                overlay_area,
+            );
+        }
+        Ok(())
+    }
+
+    fn displayed_chart(&self) -> Option<&CachedChart> {
+        let key = self.displayed.as_ref()?;
+        self.cache.entries.peek(key)
+    }
+
+    /// The scale asked for, and the linear one the displayed chart falls back to where that is
+    /// undefined, see [`ValueAxis::scaled`](crate::chart::axis::ValueAxis::scaled).
+    fn scale_title(&self) -> String {
+        let shown = self
+            .displayed
+            .as_ref()
+            .filter(|key| key.spec.scale == self.spec.scale)
+            .and_then(|_| self.displayed_chart())
+            .map(|chart| chart.geometry.price_axis.scale());
+        match shown {
+            Some(shown) if shown != self.spec.scale => format!("{shown} ({} n/a)", self.spec.scale),
+            _ => self.spec.scale.to_string(),
+        }
+    }
+
+    fn displayed_details(&self) -> Option<&ChartDetails> {
+        self.displayed_chart().map(|chart| &chart.details)
+    }
+
+    /// A crosshair over the displayed chart, drawn in cells on top of the image rather than into
+    /// it, so moving it does not redraw and re-encode the chart. The price at the horizontal line
+    /// sits on the price axis and the bar under the vertical one is inspected next to it.
+    fn render_crosshair(&self, f: &mut Frame<'_>, area: Rect, theme: &Theme) {
+        let Some(cursor) = self.cursor else {
+            return;
+        };
+        if area.is_empty() {
+            return;
+        }
+        let Some(chart) = self.displayed_chart() else {
+            return;
+        };
+        let (geometry, details) = (&chart.geometry, &chart.details);
+        let Some(bar) = details.bars.get(cursor.ix).or(details.bars.last()) else {
+            return;
+        };
+        let ix = cursor.ix.min(details.bars.len() - 1);
+        let (font_width, font_height) = self.picker.font_size();
+        let (font_width, font_height) = (font_width.max(1) as i32, font_height.max(1) as i32);
+        let to_column = |x: i32| (x / font_width).clamp(0, area.width as i32 - 1) as u16;
+        let to_row = |y: i32| (y / font_height).clamp(0, area.height as i32 - 1) as u16;
+
+        let column = to_column(geometry.bar_x[ix]);
+        let columns = to_column(geometry.plot_x.start)..to_column(geometry.plot_x.end - 1) + 1;
+        let price_rows = to_row(geometry.price_y.start)..to_row(geometry.price_y.end - 1) + 1;
+        // a price pane squeezed to nothing by the lower ones
+        if price_rows.is_empty() {
+            return;
+        }
+        let row = self
+            .cursor_row()
+            .unwrap_or_default()
+            .clamp(price_rows.start, price_rows.end - 1);
+        let price = geometry.price_at(row as i32 * font_height + font_height / 2);
+
+        let style = Style::new().fg(theme.accent);
+        let buffer = f.buffer_mut();
+        let mut put = |column: u16, row: u16, symbol: &str, style: Style| {
+            let position = Position::new(area.x + column, area.y + row);
+            if let Some(cell) = buffer.cell_mut(position) {
+                cell.set_symbol(symbol).set_style(style);
+            }
+        };
+        for y in to_row(geometry.price_y.start)..to_row(geometry.plot_bottom - 1) + 1 {
+            put(column, y, "│", style);
+        }
+        for x in columns.clone() {
+            put(x, row, if x == column { "┼" } else { "─" }, style);
+        }
+        if let Some(price) = price {
+            let label = format!(" {} ", format_price(price, bar.close));
+            let label_style = Style::new().fg(theme.header_fg).bg(theme.accent);
+            for (offset, symbol) in label.chars().enumerate() {
+                put(
+                    columns.end + offset as u16,
+                    row,
+                    &symbol.to_string(),
+                    label_style,
+                );
+            }
+        }
+
+        // on the other half of the chart, so it does not hide the bar
+        let lines = self.inspect(details, ix, theme);
+        let width =
+            (lines.iter().map(Line::width).max().unwrap_or_default() as u16 + 2).min(area.width);
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let x = if column < (columns.start + columns.end) / 2 {
+            columns.end.saturating_sub(width)
+        } else {
+            columns.start
+        };
+        let inspector_area =
+            Rect::new(area.x + x, area.y + price_rows.start, width, height).intersection(area);
+        f.render_widget(Clear, inspector_area);
+        f.render_widget(
+            Paragraph::new(lines).block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::new().fg(theme.accent)),
+            ),
+            inspector_area,
+        );
+    }
+
+    /// Cell row of the horizontal crosshair line within the chart, that of the close of the bar
+    /// under the vertical one until moved up or down.
+    fn cursor_row(&self) -> Option<u16> {
+        let cursor = self.cursor?;
+        if cursor.row.is_some() {
+            return cursor.row;
+        }
+        let chart = self.displayed_chart()?;
+        let bar = chart
+            .details
+            .bars
+            .get(cursor.ix)
+            .or(chart.details.bars.last())?;
+        let font_height = self.picker.font_size().1.max(1) as i32;
+        Some((chart.geometry.y_of(bar.close).max(0) / font_height) as u16)
+    }
+
+    /// Time, prices, change and indicator values of bar `ix` of the chart of `details`.
+    fn inspect(&self, details: &ChartDetails, ix: usize, theme: &Theme) -> Vec<Line<'static>> {
+        let bar = &details.bars[ix];
+        let previous_close = match ix {
+            0 => details.previous_close,
+            ix => Some(details.bars[ix - 1].close),
+        };
+        let price = |value: f32| format_price(value, bar.close);
+        let mut rows = vec![
+            ("Open", price(bar.open)),
+            ("High", price(bar.high)),
+            ("Low", price(bar.low)),
+            ("Close", price(bar.close)),
+            ("Range", price(bar.high - bar.low)),
+        ];
+        if let Some(previous) = previous_close {
+            let change = bar.close - previous;
+            let percent = 100.0 * change / previous;
+            let sign = if change < 0.0 { "" } else { "+" };
+            rows.insert(
+                4,
+                (
+                    "Change",
+                    format!("{sign}{} ({sign}{percent:.2}%)", price(change)),
+                ),
+            );
+        }
+        if let Some(volume) = bar.volume {
+            rows.push(("Volume", format!("{volume:.0}")));
+        }
+        if let Some(trades) = bar.trades {
+            rows.push(("Trades", trades.to_string()));
+        }
+        let readings = details.readings.iter().filter_map(|(name, values)| {
+            let value = values.get(ix).copied().flatten()?;
+            // not all prices, e.g. the RSI
+            Some((name.as_str(), format_price(value, value)))
+        });
+        let rows = rows.into_iter().chain(readings).collect::<Vec<_>>();
+        let name_width = rows
+            .iter()
+            .map(|(name, _)| name.len())
+            .max()
+            .unwrap_or_default()
+            + 1;
+
+        let time = bar.time.with_timezone(&self.spec.tz);
+        let mut lines = vec![Line::styled(
+            time.format("%a %d %b %Y %H:%M").to_string(),
+            Style::new().fg(theme.text).bold(),
+        )];
+        lines.extend(rows.into_iter().map(|(name, value)| {
+            Line::from(vec![
+                Span::styled(format!("{name:<name_width$}"), Style::new().fg(theme.muted)),
+                Span::styled(value, Style::new().fg(theme.text)),
+            ])
+        }));
+        lines
+    }
+
+    /// The closes of the displayed chart as a strip of bars, those in view highlighted.
+    fn render_overview(&self, f: &mut Frame<'_>, area: Rect, theme: &Theme) {
+        let Some((closes, viewport)) = self
+            .displayed
+            .as_ref()
+            .zip(self.displayed_details())
+            .map(|(key, details)| (&details.closes, key.spec.viewport))
+        else {
+            return;
+        };
+        let (Some(low), Some(high)) = (
+            closes.iter().copied().reduce(f32::min),
+            closes.iter().copied().reduce(f32::max),
+        ) else {
+            return;
+        };
+        let window = viewport.window(closes.len());
+        let width = area.width as usize;
+        let columns = (0..width).map(|column| {
+            // the bars of the column, at least one even if there are fewer bars than columns
+            let start = column * closes.len() / width;
+            let end = ((column + 1) * closes.len() / width).max(start + 1);
+            let level = match high - low {
+                0.0 => 0.0,
+                range => (closes[end - 1] - low) / range,
             };
+            let symbol =
+                OVERVIEW_LEVELS[(level * (OVERVIEW_LEVELS.len() - 1) as f32).round() as usize];
+            let color = if start < window.end && window.start < end {
+                theme.accent
+            } else {
+                theme.muted
+            };
+            Span::styled(symbol.to_string(), Style::new().fg(color))
+        });
+        f.render_widget(Line::from(columns.collect::<Vec<_>>()), area);
+    }
+
+    /// The latest candlestick patterns of the displayed chart, colored by their bias.
+    fn render_patterns(&self, f: &mut Frame<'_>, area: Rect, theme: &Theme) {
+        let patterns = self
+            .displayed_details()
+            .map(|details| details.patterns.as_slice())
+            .unwrap_or_default();
+        let items = patterns.iter().map(|(time, pattern)| {
+            let color = match pattern.bias() {
+                Bias::Bullish => theme.bullish,
+                Bias::Bearish => theme.bearish,
+                Bias::Neutral => theme.text,
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    time.with_timezone(&self.spec.tz)
+                        .format("%d %H:%M ")
+                        .to_string(),
+                    Style::new().fg(theme.muted),
+                ),
+                Span::styled(pattern.to_string(), Style::new().fg(color)),
+            ]))
+        });
+        f.render_widget(
+            List::new(items).block(
+                Block::new()
+                    .borders(Borders::LEFT)
+                    .border_type(BorderType::Plain)
+                    .border_style(Style::new().fg(theme.muted))
+                    .title(" Patterns ")
+                    .padding(Padding::left(1)),
+            ),
+            area,
+        );
+    }
+
+    /// Support and resistance zones of the displayed chart, highest first.
+    fn render_zones(&self, f: &mut Frame<'_>, area: Rect, theme: &Theme) {
+        let zones = self
+            .displayed_details()
+            .map(|details| details.zones.as_slice())
+            .unwrap_or_default();
+        let rows = zones.iter().map(|zone| {
+            let range = format!(
+                "{}–{}",
+                format_price(zone.range.start, zone.range.end),
+                format_price(zone.range.end, zone.range.end)
+            );
+            let last_touch = zone.last_touch.with_timezone(&self.spec.tz);
+            Row::new([
+                Cell::new(range),
+                Cell::new(format!("{}×", zone.touches)),
+                Cell::new(last_touch.format("%a %d %H:%M").to_string()).style(theme.muted),
+            ])
+        });
+        f.render_widget(
+            Table::new(
+                rows,
+                [
+                    Constraint::Length(15),
+                    Constraint::Length(3),
+                    Constraint::Length(12),
+                ],
+            )
+            .column_spacing(1)
+            .style(Style::new().fg(theme.text))
+            .header(
+                Row::new(["Zone", "Tch", "Last touch"]).style(
+                    Style::default()
+                        .bg(theme.header_bg)
+                        .fg(theme.header_fg)
+                        .bold(),
+                ),
+            )
+            .block(
+                Block::new()
+                    .borders(Borders::TOP | Borders::RIGHT)
+                    .border_type(BorderType::Plain)
+                    .border_style(Style::new().fg(theme.muted))
+                    .title(" Zones "),
+            ),
+            area,
+        );
+    }
+
+    /// Spawns a job fetching the bars for the current selection from the [`DataProvider`], then
+    /// drawing and encoding the chart on a blocking task, superseding any job still in flight.
+    async fn request_image(&mut self) -> Result<()> {
+        let Some(image_area) = self.image_area else {
+            return Ok(()); // nothing rendered yet, the first frame will request the image
+        };
+        let Some(instrument) = self.state.selected().and_then(|i| self.instruments.get(i)) else {
+            return Ok(());
         };
+        let (symbol, asset_class) = (instrument.symbol().to_string(), instrument.asset_class);
 
+        // whatever is in flight is stale now, even if the new chart comes from the cache
+        self.error = None;
+        let job_id = self.latest_job.fetch_add(1, Ordering::SeqCst) + 1;
+        if let Some(job) = self.job.take() {
+            job.abort(); // only effective if the stale job has not started yet
+        }
+
+        let revision = match self.provider.revision(&symbol).await {
+            std::result::Result::Ok(revision) => revision,
+            // the bars can't be read either, like a failed job
+            Err(err) => {
+                self.rendering = false;
+                self.displayed = None;
+                self.error = Some(format!("{err:#}"));
+                return Ok(());
+            }
+        };
+        let key = ChartKey {
+            symbol,
+            revision,
+            size: cell_rect_to_pixel_size(&image_area, self.picker.font_size()),
+            spec: self.spec.clone(),
+        };
+
+        if self.cache.entries.promote(&key) {
+            self.cache.hits += 1;
+            self.rendering = false;
+            self.displayed = Some(key);
+            return Ok(());
+        }
+        self.cache.misses += 1;
+        self.rendering = true;
+
+        let picker = self.picker.clone();
+        let provider = self.provider.clone();
+        let latest_job = self.latest_job.clone();
+        let tx = self.tx.clone();
+        let renderer = self.renderer;
+        self.job = Some(tokio::spawn(async move {
+            let result = match provider.bars(&key.symbol, ALL_TIME).await {
+                Err(err) => Some(Err(err)),
+                std::result::Result::Ok(series) => tokio::task::spawn_blocking(move || {
+                    let is_stale = || latest_job.load(Ordering::SeqCst) != job_id;
+                    let series = resample(series, key.spec.timeframe, asset_class);
+                    let details = ChartDetails::of(&series, &key.spec);
+                    let chart = match renderer {
+                        // nothing to encode, the view draws the bars of the details
+                        Renderer::Text => TextChart::new(
+                            &details.bars,
+                            key.spec.kind,
+                            key.spec.scale,
+                            key.spec.theme.theme(),
+                        )
+                        .map(|chart| Some((None, chart.geometry(image_area, picker.font_size())))),
+                        Renderer::Auto | Renderer::Image => {
+                            render_chart(&key, &series, asset_class, &picker, image_area, is_stale)
+                                .map(|chart| {
+                                    chart.map(|(protocol, geometry)| (Some(protocol), geometry))
+                                })
+                        }
+                    };
+                    chart
+                        .map(|chart| {
+                            chart.map(|(protocol, geometry)| ChartImage {
+                                details,
+                                key,
+                                protocol,
+                                geometry,
+                            })
+                        })
+                        .transpose()
+                })
+                .await
+                .unwrap_or_else(|err| Some(Err(err.into()))),
+            };
+            // `None`: superseded by a newer selection, nobody is waiting for this one
+            if let Some(result) = result {
+                let result = result.map(Box::new);
+                let _ = tx.send(Action::ImageReady { job_id, result });
+            }
+        }));
         Ok(())
     }
 }
 
+/// Everything that determines the pixels of a chart.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub(crate) struct ChartKey {
+    symbol: String,
+    /// of the bars, see [`DataProvider::revision`]
+    revision: u64,
+    /// (width, height) in pixels, see [`cell_rect_to_pixel_size`]
+    size: (u16, u16),
+    spec: ChartSpec,
+}
+
+/// An encoded chart, as delivered by a background job.
+pub(crate) struct ChartImage {
+    key: ChartKey,
+    /// `None` when charts are drawn as text, from the details
+    protocol: Option<StatefulProtocol>,
+    geometry: ChartGeometry,
+    details: ChartDetails,
+}
+
+/// What a chart job found in the bars besides the picture, listed next to the chart.
+#[derive(Default)]
+struct ChartDetails {
+    /// of all bars, in or out of view, for the overview strip
+    closes: Vec<f32>,
+    /// the bars in view, for the crosshair
+    bars: Vec<Bar>,
+    /// of the bar before the first one in view, if any
+    previous_close: Option<f32>,
+    /// values of the indicators at the bars in view, see [`readings`]
+    readings: Vec<(String, Vec<Option<f32>>)>,
+    /// latest candlestick patterns in view first, by the opening time of the completing bar
+    patterns: Vec<(DateTime<Utc>, Pattern)>,
+    /// support and resistance zones of the bars in view, highest first
+    zones: Vec<Zone>,
+}
+impl ChartDetails {
+    /// Only what `spec` asks for, the scans are not free. Like the chart, from the bars of its
+    /// [`ChartKind`](crate::chart::kind::ChartKind) within its [`Viewport`].
+    fn of(series: &Series, spec: &ChartSpec) -> Self {
+        let series = spec.kind.transform(series, spec.renko_box);
+        let bars = series.bars();
+        let window = spec.viewport.window(bars.len());
+        let mut details = ChartDetails {
+            closes: series.closes(),
+            bars: bars[window.clone()].to_vec(),
+            previous_close: window
+                .start
+                .checked_sub(1)
+                .map(|previous| bars[previous].close),
+            readings: readings(&series, spec),
+            ..ChartDetails::default()
+        };
+        if spec.show_patterns {
+            details.patterns = scan(bars)
+                .into_iter()
+                .rev()
+                .filter(|found| window.contains(&found.ix))
+                .take(RECENT_PATTERNS)
+                .map(|found| (bars[found.ix].time, found.pattern))
+                .collect();
+        }
+        if spec.show_zones {
+            details.zones = zones(&bars[window], spec.swing_strength);
+        }
+        details
+    }
+}
+
+struct CachedChart {
+    /// `None` when charts are drawn as text, from the details
+    protocol: Option<StatefulProtocol>,
+    geometry: ChartGeometry,
+    details: ChartDetails,
+}
+
+/// Position of the crosshair, see [`InstrumentList::render_crosshair`].
+#[derive(Clone, Copy)]
+struct Cursor {
+    /// bar in view under the vertical line
+    ix: usize,
+    /// cell row of the horizontal line within the chart, `None` to follow the close of the bar
+    row: Option<u16>,
+}
+
+/// LRU of encoded charts, with hit/miss counters for the debug overlay.
+struct ChartCache {
+    entries: LruCache<ChartKey, CachedChart>,
+    hits: u64,
+    misses: u64,
+}
+impl ChartCache {
+    fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            entries: LruCache::new(capacity),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Drops all charts (but keeps the counters), e.g. on resize or when the data changed.
+    fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// Draws the chart of `series` described by `key` and encodes it for `image_area`.
+///
+/// Blocking; meant to be run via [`tokio::task::spawn_blocking`]. Returns `Ok(None)` when
+/// `is_stale` reports the job was superseded before drawing or before the (expensive) encoding.
+fn render_chart(
+    key: &ChartKey,
+    series: &Series,
+    asset_class: AssetClass,
+    picker: &Picker,
+    image_area: Rect,
+    is_stale: impl Fn() -> bool,
+) -> Result<Option<(StatefulProtocol, ChartGeometry)>> {
+    if is_stale() {
+        return Ok(None);
+    }
+    let (width, height) = key.size;
+    let (image, geometry) = chart_image(
+        series,
+        asset_class,
+        &key.spec,
+        (width as u32, height as u32),
+    )
+    .wrap_err_with(|| format!("Charting {}", key.symbol))?;
+
+    if is_stale() {
+        return Ok(None);
+    }
+
+    let mut protocol = picker.new_resize_protocol(DynamicImage::ImageRgb8(image));
+    // encode here rather than on the first render, which would block the draw loop
+    let resize = Resize::default();
+    if let Some(area) = protocol.needs_resize(&resize, image_area) {
+        protocol.resize_encode(&resize, area);
+    }
+    if let Some(result) = protocol.last_encoding_result() {
+        result?;
+    }
+    Ok(Some((protocol, geometry)))
+}
+
+/// `value` with as many decimals as prices around `reference` need, e.g. 4 for forex.
+fn format_price(value: f32, reference: f32) -> String {
+    let decimals = match reference.abs() {
+        r if r >= 1000.0 => 0,
+        r if r >= 10.0 => 2,
+        _ => 4,
+    };
+    format!("{value:.decimals$}")
+}
+
 /// (width, height) in pixels
 fn cell_rect_to_pixel_size(rect: &Rect, font_size: (u16, u16)) -> (u16, u16) {
     (rect.width * font_size.0, rect.height * font_size.1)
@@ -1,15 +1,66 @@
 use color_eyre::{Result, eyre::Ok};
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
 use ratatui::{
     Frame,
-    layout::Rect,
-    style::{Color, Modifier, Style},
-    text::{Line, Span, ToSpan},
+    layout::{Alignment, Margin, Position, Rect},
+    style::{Modifier, Style},
+    text::{Line, ToSpan},
     widgets::{Block, BorderType, List, ListItem, ListState, Paragraph, Wrap},
 };
+use serde::{Deserialize, Serialize};
+use strum_macros::Display;
 use tokio::sync::mpsc::UnboundedSender;
 
-use crate::{Action, HOTKEY_STYLE};
+use crate::{
+    Action,
+    config::Config,
+    hotkeys::{HotkeyLine, HotkeyTitles},
+    keymap::{Command, Keymap},
+    theme::Theme,
+};
+
+/// What the keys do in the list of notes, `[keys.notes]` in the config file.
+#[derive(Serialize, Deserialize, Display, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub(crate) enum NotesCommand {
+    Quit,
+    ShowInstruments,
+    SelectNext,
+    SelectPrevious,
+    Edit,
+    New,
+    Delete,
+    NextTheme,
+}
+impl Command for NotesCommand {
+    const DEFAULTS: &'static [(Self, &'static [KeyCode])] = &[
+        (Self::Quit, &[KeyCode::Char('q')]),
+        (Self::ShowInstruments, &[KeyCode::Char('I')]),
+        (Self::SelectNext, &[KeyCode::Char('j'), KeyCode::Down]),
+        (Self::SelectPrevious, &[KeyCode::Char('k'), KeyCode::Up]),
+        (Self::Edit, &[KeyCode::Char('l')]),
+        (Self::New, &[KeyCode::Char('n')]),
+        (Self::Delete, &[KeyCode::Char('d')]),
+        (Self::NextTheme, &[KeyCode::Char('T')]),
+    ];
+}
+
+/// What the keys other than characters do while writing a note, `[keys.note-editor]` in the
+/// config file. Edits of existing notes are kept either way.
+#[derive(Serialize, Deserialize, Display, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub(crate) enum EditorCommand {
+    Save,
+    Cancel,
+}
+impl Command for EditorCommand {
+    const DEFAULTS: &'static [(Self, &'static [KeyCode])] = &[
+        (Self::Save, &[KeyCode::Enter]),
+        (Self::Cancel, &[KeyCode::Esc]),
+    ];
+}
 
 #[derive(PartialEq)]
 pub(crate) struct Note {
@@ -21,93 +72,115 @@ pub(crate) struct Notes {
     pub state: ListState,
     pub mode: NotesMode,
     pub input_value: String,
+    /// area of the list as last rendered, for clicks on its notes
+    list_area: Option<Rect>,
+    hotkeys: HotkeyTitles,
+    keymap: Keymap<NotesCommand>,
+    editor_keymap: Keymap<EditorCommand>,
 }
 
 impl Notes {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(config: &Config) -> Self {
         Notes {
             items: demo_notes(),
             state: ListState::default().with_selected(Some(0)),
             mode: NotesMode::DisplayList,
             input_value: String::default(),
+            list_area: None,
+            hotkeys: HotkeyTitles::default(),
+            keymap: config.keys.notes.clone(),
+            editor_keymap: config.keys.note_editor.clone(),
         }
     }
 
-    pub(crate) fn render(&mut self, f: &mut Frame<'_>, my_area: Rect) {
+    pub(crate) fn render(&mut self, f: &mut Frame<'_>, my_area: Rect, theme: &Theme) {
+        self.hotkeys.clear();
+        self.list_area = None;
+        // the room between the corners of the block
+        let hints_width = my_area.width.saturating_sub(2);
         match self.mode {
-            NotesMode::DisplayList => f.render_stateful_widget(
-                List::new(self.items.iter().map(|i| {
-                    ListItem::new(i.content.to_string()).style(Color::LightMagenta)
-                }))
-                .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
-                .block(
-                    Block::bordered()
-                        .border_type(BorderType::Rounded)
-                        .style(Color::LightMagenta)
-                        .title(Line::from(" Notes ").left_aligned())
-                        .title(
-                            Line::from(vec![
-                                Span::styled("I", HOTKEY_STYLE),
-                                "nstruments──".into(),
-                                Span::styled("q", HOTKEY_STYLE),
-                                "uit ".into(),
-                            ])
-                            .right_aligned(),
-                        )
-                        .title_bottom(
-                            Line::from(vec![
-                                Span::styled("j(↓)/h(↑)", HOTKEY_STYLE),
-                                "(select)──".into(),
-                                Span::styled("l", HOTKEY_STYLE),
-                                "(edit)──".into(),
-                                Span::styled("n", HOTKEY_STYLE),
-                                "ew──".into(),
-                                Span::styled("d", HOTKEY_STYLE),
-                                "elete".into(),
-                            ])
-                            .left_aligned(),
+            NotesMode::DisplayList => {
+                let title = HotkeyLine::from(Line::from(" Notes ").left_aligned());
+                let mut views = self.keymap.hints(
+                    &[
+                        (&[NotesCommand::ShowInstruments], "Instruments"),
+                        (&[NotesCommand::Quit], "quit"),
+                    ],
+                    u16::MAX,
+                );
+                views.push(" ");
+                let views = views.alignment(Alignment::Right);
+                let hints = self.keymap.hints(
+                    &[
+                        (
+                            &[NotesCommand::SelectNext, NotesCommand::SelectPrevious],
+                            "select",
                         ),
-                ),
-                my_area,
-                &mut self.state,
-            ),
+                        (&[NotesCommand::Edit], "edit"),
+                        (&[NotesCommand::New], "new"),
+                        (&[NotesCommand::Delete], "delete"),
+                        (&[NotesCommand::NextTheme], "Theme"),
+                    ],
+                    hints_width,
+                );
+                self.hotkeys.top(my_area, &[&title, &views]);
+                self.hotkeys.bottom(my_area, &[&hints]);
+                self.list_area = Some(my_area);
+                f.render_stateful_widget(
+                    List::new(
+                        self.items
+                            .iter()
+                            .map(|i| ListItem::new(i.content.to_string()).style(theme.notes)),
+                    )
+                    .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+                    .block(
+                        Block::bordered()
+                            .border_type(BorderType::Rounded)
+                            .style(theme.notes)
+                            .title(title.line)
+                            .title(views.line)
+                            .title_bottom(hints.line),
+                    ),
+                    my_area,
+                    &mut self.state,
+                )
+            }
             NotesMode::AddNew => {
+                let hints = self.editor_keymap.hints(
+                    &[
+                        (&[EditorCommand::Save], "save"),
+                        (&[EditorCommand::Cancel], "back"),
+                    ],
+                    hints_width,
+                );
+                self.hotkeys.bottom(my_area, &[&hints]);
                 f.render_widget(
                     Paragraph::new(self.input_value.to_string())
                         .wrap(Wrap { trim: true })
                         .block(
                             Block::bordered()
                                 .border_type(BorderType::Rounded)
-                                .style(Color::LightGreen)
+                                .style(theme.new_note)
                                 .title(" New Note ".to_span().into_left_aligned_line())
-                                .title_bottom(
-                                    Line::from(vec![
-                                        Span::styled("Enter", HOTKEY_STYLE),
-                                        "(save)──".into(),
-                                        Span::styled("Esc", HOTKEY_STYLE),
-                                        "(back)".into(),
-                                    ])
-                                    .left_aligned(),
-                                ),
+                                .title_bottom(hints.line),
                         ),
                     my_area,
                 );
             }
             NotesMode::Edit { selected_ix } => {
                 if let Some(note) = self.items.get(selected_ix) {
+                    let hints = self.editor_keymap.hints(
+                        &[(&[EditorCommand::Save, EditorCommand::Cancel], "back")],
+                        hints_width,
+                    );
+                    self.hotkeys.bottom(my_area, &[&hints]);
                     f.render_widget(
                         Paragraph::new(note.content.to_string()).block(
                             Block::bordered()
                                 .border_type(BorderType::Rounded)
-                                .style(Color::LightCyan)
+                                .style(theme.edit_note)
                                 .title(" Edit Note ".to_span().into_left_aligned_line())
-                                .title_bottom(
-                                    Line::from(vec![
-                                        Span::styled("Enter/Esc", HOTKEY_STYLE),
-                                        "(back)".into(),
-                                    ])
-                                    .left_aligned(),
-                                ),
+                                .title_bottom(hints.line),
                         ),
                         my_area,
                     );
@@ -122,13 +195,13 @@ impl Notes {
         tx: &UnboundedSender<Action>,
     ) -> Result<()> {
         match self.mode {
-            NotesMode::DisplayList => match key_event.code {
-                KeyCode::Char('j') | KeyCode::Down => self.state.select_next(),
-                KeyCode::Char('k') | KeyCode::Up => self.state.select_previous(),
-                KeyCode::Char('n') => {
+            NotesMode::DisplayList => match self.keymap.command(key_event.code) {
+                Some(NotesCommand::SelectNext) => self.state.select_next(),
+                Some(NotesCommand::SelectPrevious) => self.state.select_previous(),
+                Some(NotesCommand::New) => {
                     self.mode = NotesMode::AddNew;
                 }
-                KeyCode::Char('l') => {
+                Some(NotesCommand::Edit) => {
                     match self.state.selected() {
                         Some(selected_ix) => self.mode = NotesMode::Edit { selected_ix },
                         None => {
@@ -136,44 +209,51 @@ impl Notes {
                         }
                     };
                 }
-                KeyCode::Char('d') => {
+                Some(NotesCommand::Delete) => {
                     if let Some(selected_index) = self.state.selected() {
                         self.items.remove(selected_index);
                     };
                 }
-                KeyCode::Char('I') => tx.send(Action::ChangeView(crate::View::Instruments))?,
-                KeyCode::Char('q') => tx.send(Action::Quit)?,
-                _ => {}
-            },
-            NotesMode::AddNew => match key_event.code {
-                KeyCode::Char(c) => self.input_value.push(c),
-                KeyCode::Backspace => {
-                    self.input_value.pop();
+                Some(NotesCommand::ShowInstruments) => {
+                    tx.send(Action::ChangeView(crate::View::Instruments))?
                 }
-                KeyCode::Esc => {
+                Some(NotesCommand::NextTheme) => tx.send(Action::NextTheme)?,
+                Some(NotesCommand::Quit) => tx.send(Action::Quit)?,
+                None => {}
+            },
+            NotesMode::AddNew => match self.editor_keymap.command(key_event.code) {
+                Some(EditorCommand::Cancel) => {
                     self.input_value.clear();
                     self.mode = NotesMode::DisplayList;
                 }
-                KeyCode::Enter => {
+                Some(EditorCommand::Save) => {
                     self.items.push(Note {
                         content: self.input_value.to_string(),
                     });
                     self.input_value.clear();
                     self.mode = NotesMode::DisplayList;
                 }
-                _ => {}
+                None => match key_event.code {
+                    KeyCode::Char(c) => self.input_value.push(c),
+                    KeyCode::Backspace => {
+                        self.input_value.pop();
+                    }
+                    _ => {}
+                },
             },
             NotesMode::Edit { selected_ix } => {
                 if let Some(note) = self.items.get_mut(selected_ix) {
-                    match key_event.code {
-                        KeyCode::Char(c) => note.content.push(c),
-                        KeyCode::Backspace => {
-                            note.content.pop();
-                        }
-                        KeyCode::Enter | KeyCode::Esc => {
+                    match self.editor_keymap.command(key_event.code) {
+                        Some(EditorCommand::Save | EditorCommand::Cancel) => {
                             self.mode = NotesMode::DisplayList;
                         }
-                        _ => {}
+                        None => match key_event.code {
+                            KeyCode::Char(c) => note.content.push(c),
+                            KeyCode::Backspace => {
+                                note.content.pop();
+                            }
+                            _ => {}
+                        },
                     }
                 } else {
                     self.mode = NotesMode::DisplayList;
@@ -183,8 +263,35 @@ impl Notes {
         Ok(())
     }
 
-    pub(crate) async fn on_action(&mut self, _action: Option<Action>) -> Result<()> {
-        unimplemented!() // this screen supports no actions yet
+    /// Clicks press the hotkeys in the titles and select notes, the wheel moves the selection.
+    pub(crate) async fn on_mouse(
+        &mut self,
+        mouse_event: MouseEvent,
+        tx: &UnboundedSender<Action>,
+    ) -> Result<()> {
+        let position = Position::new(mouse_event.column, mouse_event.row);
+        match mouse_event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                if let Some(key) = self.hotkeys.key_at(position) {
+                    return self.on_event(KeyEvent::from(key), tx).await;
+                }
+                let Some(area) = self.list_area else {
+                    return Ok(());
+                };
+                // rows start below the top border
+                let inner = area.inner(Margin::new(1, 1));
+                if inner.contains(position) {
+                    let ix = self.state.offset() + (position.y - inner.y) as usize;
+                    if ix < self.items.len() {
+                        self.state.select(Some(ix));
+                    }
+                }
+            }
+            MouseEventKind::ScrollDown if self.list_area.is_some() => self.state.select_next(),
+            MouseEventKind::ScrollUp if self.list_area.is_some() => self.state.select_previous(),
+            _ => {}
+        }
+        Ok(())
     }
 }
 